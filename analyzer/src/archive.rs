@@ -0,0 +1,341 @@
+//! Results files as they are published: a zip archive holding one or more runs, a single CSV or
+//! preprocessed runs. The runs are listed when the file is opened, a run is only decompressed and
+//! parsed when it is read.
+
+use crate::model::{ResultsParser, TestResults};
+use crate::preprocessed;
+use async_zip::base::read::mem::ZipFileReader;
+use async_zip::base::read::{WithEntry, ZipEntryReader};
+use futures_lite::io::{AsyncReadExt, Cursor};
+use std::fmt;
+
+const ZIP_MAGIC: &[u8; 4] = b"PK\x03\x04";
+
+/// Rows handed over in the first batch of a run. The next batches double in size, so the first
+/// rows show up quickly without flooding the caller with batches on a full run.
+pub const FIRST_BATCH: usize = 1000;
+// Decompressed and parsed at a time
+const CHUNK_SIZE: usize = 1 << 20;
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct ArchiveError(String);
+
+impl fmt::Display for ArchiveError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}", self.0)
+    }
+}
+
+impl std::error::Error for ArchiveError {}
+
+enum Source {
+    Csv(Vec<u8>),
+    // Indices of the zip entries holding a run
+    Zip(ZipFileReader, Vec<usize>),
+    // Written by the `preprocess` tool, decoded as soon as it is opened
+    Preprocessed(Vec<TestResults>),
+}
+
+pub struct Archive {
+    /// Name of every run in the archive, in archive order
+    pub runs: Vec<String>,
+    source: Source,
+}
+
+impl Archive {
+    /// Lists the runs of a results file, a CSV holds a single run named `name`
+    pub async fn open(name: &str, bytes: Vec<u8>) -> Result<Self, ArchiveError> {
+        if preprocessed::is_preprocessed(&bytes) {
+            let (runs, results) = preprocessed::decode(&bytes)
+                .map_err(|e| ArchiveError(format!("{name}: {e}")))?
+                .into_iter()
+                .map(|run| (run.name, run.results))
+                .unzip();
+            return Ok(Self {
+                runs,
+                source: Source::Preprocessed(results),
+            });
+        }
+
+        if !bytes.starts_with(ZIP_MAGIC) {
+            return Ok(Self {
+                runs: vec![name.to_string()],
+                source: Source::Csv(bytes),
+            });
+        }
+
+        let invalid = |e| ArchiveError(format!("{name}: {e}"));
+        let zip = ZipFileReader::new(bytes).await.map_err(invalid)?;
+        let mut files = Vec::new();
+        for (i, entry) in zip.file().entries().iter().enumerate() {
+            if !entry.dir().map_err(invalid)? {
+                files.push((i, entry.filename().as_str().map_err(invalid)?.to_string()));
+            }
+        }
+        // Archives may ship other files next to the results, only keep the CSVs if there are any
+        if files.iter().any(|(_, name)| name.ends_with(".csv")) {
+            files.retain(|(_, name)| name.ends_with(".csv"));
+        }
+        if files.is_empty() {
+            return Err(ArchiveError(format!("{name} does not contain any file")));
+        }
+
+        let (indices, runs) = files.into_iter().unzip();
+        Ok(Self {
+            runs,
+            source: Source::Zip(zip, indices),
+        })
+    }
+
+    /// Reads a run chunk by chunk, nothing is decompressed before the first read
+    pub async fn reader(&self, run: usize) -> Result<RunReader<'_>, ArchiveError> {
+        let name = self
+            .runs
+            .get(run)
+            .ok_or_else(|| ArchiveError(format!("No run {run} in the archive")))?;
+        let (input, size) = match &self.source {
+            Source::Csv(csv) => (Input::Csv(csv), csv.len() as u64),
+            Source::Zip(zip, indices) => {
+                let reader = zip
+                    .reader_with_entry(indices[run])
+                    .await
+                    .map_err(|e| ArchiveError(format!("{name}: {e}")))?;
+                let size = reader.entry().uncompressed_size();
+                (Input::Zip(Box::new(reader), vec![0; CHUNK_SIZE]), size)
+            }
+            Source::Preprocessed(runs) => {
+                let results = &runs[run];
+                (
+                    Input::Parsed(results),
+                    results.name_offset(results.len()) as u64,
+                )
+            }
+        };
+        Ok(RunReader {
+            name,
+            input,
+            parser: ResultsParser::default(),
+            rows: TestResults::default(),
+            position: 0,
+            size,
+            sent: 0,
+            done: false,
+        })
+    }
+
+    /// Decompresses and parses a whole run
+    pub async fn results(&self, run: usize) -> Result<TestResults, ArchiveError> {
+        let mut reader = self.reader(run).await?;
+        let mut results = TestResults::default();
+        loop {
+            let more = reader.read().await?;
+            if let Some(rows) = reader.batch() {
+                results.append(rows);
+            }
+            if !more {
+                return Ok(results);
+            }
+        }
+    }
+}
+
+enum Input<'a> {
+    // What is left to parse
+    Csv(&'a [u8]),
+    // With the buffer chunks are decompressed in
+    Zip(
+        Box<ZipEntryReader<'a, Cursor<&'a [u8]>, WithEntry<'a>>>,
+        Vec<u8>,
+    ),
+    // Already parsed, only split in batches
+    Parsed(&'a TestResults),
+}
+
+/// A run being read chunk by chunk, see [`Archive::reader`]
+pub struct RunReader<'a> {
+    name: &'a str,
+    input: Input<'a>,
+    parser: ResultsParser,
+    // Parsed rows not handed over yet
+    rows: TestResults,
+    position: u64,
+    size: u64,
+    // Rows handed over so far
+    sent: usize,
+    done: bool,
+}
+
+impl RunReader<'_> {
+    /// Bytes of the run read so far, once decompressed.
+    /// Preprocessed runs are measured in bytes of test names.
+    pub fn position(&self) -> u64 {
+        self.position
+    }
+
+    /// Bytes of the whole run, see [`RunReader::position`]
+    pub fn size(&self) -> u64 {
+        self.size
+    }
+
+    /// Rows parsed so far
+    pub fn rows(&self) -> usize {
+        self.sent + self.rows.len()
+    }
+
+    /// Reads and parses the next chunk of the run, `false` once the whole run is read
+    pub async fn read(&mut self) -> Result<bool, ArchiveError> {
+        if self.done {
+            return Ok(false);
+        }
+        let invalid = |e: &dyn fmt::Display| ArchiveError(format!("{}: {e}", self.name));
+        let rows = match &mut self.input {
+            Input::Csv(rest) => {
+                let (chunk, tail) = rest.split_at(CHUNK_SIZE.min(rest.len()));
+                *rest = tail;
+                self.position += chunk.len() as u64;
+                self.done = tail.is_empty();
+                self.parser.push(chunk).map_err(|e| invalid(&e))?
+            }
+            Input::Zip(reader, buffer) => {
+                let read = reader.read(buffer).await.map_err(|e| invalid(&e))?;
+                if read == 0 && reader.compute_hash() != reader.entry().crc32() {
+                    return Err(invalid(&"CRC32 check failed"));
+                }
+                self.position += read as u64;
+                self.done = read == 0;
+                self.parser.push(&buffer[..read]).map_err(|e| invalid(&e))?
+            }
+            Input::Parsed(results) => {
+                let start = self.sent + self.rows.len();
+                let end = (start + FIRST_BATCH.max(self.sent)).min(results.len());
+                self.position = results.name_offset(end) as u64;
+                self.done = end == results.len();
+                results.slice(start..end)
+            }
+        };
+        self.rows.append(rows);
+        if self.done {
+            let last = std::mem::take(&mut self.parser).finish();
+            self.rows.append(last.map_err(|e| invalid(&e))?);
+        }
+        Ok(!self.done)
+    }
+
+    /// The rows parsed since the last batch once there are enough of them,
+    /// or the last ones once the whole run is read
+    pub fn batch(&mut self) -> Option<TestResults> {
+        let full = self.rows.len() >= FIRST_BATCH.max(self.sent);
+        if self.rows.is_empty() || !(full || self.done) {
+            return None;
+        }
+        let rows = std::mem::take(&mut self.rows);
+        self.sent += rows.len();
+        Some(rows)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::model::parse_results;
+    use crate::preprocessed::Run;
+    use async_zip::base::write::ZipFileWriter;
+    use async_zip::{Compression, ZipEntryBuilder};
+    use futures_lite::future::block_on;
+
+    const CSV: &str = "a,Pass,1\nb,Fail,2\n";
+
+    fn zip(files: &[(&str, &str)], compression: Compression) -> Vec<u8> {
+        block_on(async {
+            let mut writer = ZipFileWriter::new(Vec::new());
+            for (name, content) in files {
+                let entry = ZipEntryBuilder::new(name.to_string().into(), compression);
+                writer
+                    .write_entry_whole(entry, content.as_bytes())
+                    .await
+                    .unwrap();
+            }
+            writer.close().await.unwrap()
+        })
+    }
+
+    #[test]
+    fn csv_is_a_single_run() {
+        let archive = block_on(Archive::open("results.csv", CSV.into())).unwrap();
+        assert_eq!(archive.runs, ["results.csv"]);
+        let results = block_on(archive.results(0)).unwrap();
+        assert_eq!(results, parse_results(CSV).unwrap());
+    }
+
+    #[test]
+    fn zip_runs_are_its_csvs() {
+        for compression in [Compression::Stored, Compression::Deflate, Compression::Zstd] {
+            let bytes = zip(
+                &[("README", "runs"), ("a.csv", CSV), ("b.csv", "c,Crash,\n")],
+                compression,
+            );
+            let archive = block_on(Archive::open("results.zip", bytes)).unwrap();
+            assert_eq!(archive.runs, ["a.csv", "b.csv"]);
+            let results = block_on(archive.results(1)).unwrap();
+            assert_eq!(results.name(0), "c");
+        }
+    }
+
+    #[test]
+    fn batches_double_in_size() {
+        let csv = (0..5000)
+            .map(|i| format!("dEQP-VK.test.{i},Pass,{i}\n"))
+            .collect::<String>();
+        let archive = block_on(Archive::open("results.csv", csv.clone().into())).unwrap();
+        let mut reader = block_on(archive.reader(0)).unwrap();
+        let mut batches = Vec::new();
+        let mut results = TestResults::default();
+        while block_on(reader.read()).unwrap() {
+            if let Some(rows) = reader.batch() {
+                batches.push(rows.len());
+                results.append(rows);
+            }
+        }
+        if let Some(rows) = reader.batch() {
+            batches.push(rows.len());
+            results.append(rows);
+        }
+        assert_eq!(batches, [5000]);
+        assert_eq!(reader.position(), reader.size());
+        assert_eq!(results, parse_results(&csv).unwrap());
+
+        let runs = [Run {
+            name: "gpu".to_string(),
+            results,
+        }];
+        let archive = block_on(Archive::open(
+            "results.bin",
+            preprocessed::encode(&runs).unwrap(),
+        ));
+        let archive = archive.unwrap();
+        let mut reader = block_on(archive.reader(0)).unwrap();
+        let mut batches = Vec::new();
+        loop {
+            let more = block_on(reader.read()).unwrap();
+            batches.extend(reader.batch().map(|rows| rows.len()));
+            if !more {
+                break;
+            }
+        }
+        assert_eq!(batches, [1000, 1000, 2000, 1000]);
+        assert_eq!(block_on(archive.results(0)).unwrap(), runs[0].results);
+    }
+
+    #[test]
+    fn preprocessed_runs() {
+        let runs = [Run {
+            name: "gpu".to_string(),
+            results: parse_results(CSV).unwrap(),
+        }];
+        let bytes = preprocessed::encode(&runs).unwrap();
+        let archive = block_on(Archive::open("results.bin", bytes)).unwrap();
+        assert_eq!(archive.runs, ["gpu"]);
+        assert_eq!(block_on(archive.results(0)).unwrap(), runs[0].results);
+        assert!(block_on(archive.results(1)).is_err());
+    }
+}
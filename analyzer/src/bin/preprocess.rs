@@ -0,0 +1,108 @@
+//! Checks results CSVs and writes them as the preprocessed binary the viewer loads without parsing,
+//! one run per CSV: `cargo run -p vulkan-cts-analyzer --bin preprocess -- assets/results.csv -o assets/results.bin`
+
+use std::error::Error;
+use std::path::{Path, PathBuf};
+use std::process::ExitCode;
+use std::str::FromStr;
+use vulkan_cts_analyzer::model::{TestStatus, parse_results};
+use vulkan_cts_analyzer::preprocessed::{self, Run};
+
+const USAGE: &str = "Usage: preprocess [RESULTS.csv...] [-o OUTPUT]
+
+Checks every row of the results CSVs (assets/results.csv by default) and writes
+them as a preprocessed binary (assets/results.bin by default), one run per CSV.";
+
+const DEFAULT_INPUT: &str = "assets/results.csv";
+const DEFAULT_OUTPUT: &str = "assets/results.bin";
+
+// Invalid rows printed before the rest are summarized
+const MAX_REPORTED_ROWS: usize = 20;
+
+fn main() -> ExitCode {
+    match run() {
+        Ok(()) => ExitCode::SUCCESS,
+        Err(e) => {
+            eprintln!("error: {e}");
+            ExitCode::FAILURE
+        }
+    }
+}
+
+fn run() -> Result<(), Box<dyn Error>> {
+    let mut inputs = Vec::new();
+    let mut output = PathBuf::from(DEFAULT_OUTPUT);
+    let mut args = std::env::args().skip(1);
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "-o" | "--output" => output = args.next().ok_or("`--output` needs a path")?.into(),
+            "-h" | "--help" => {
+                println!("{USAGE}");
+                return Ok(());
+            }
+            _ => inputs.push(PathBuf::from(arg)),
+        }
+    }
+    if inputs.is_empty() {
+        inputs.push(PathBuf::from(DEFAULT_INPUT));
+    }
+
+    let mut runs = Vec::with_capacity(inputs.len());
+    for input in &inputs {
+        let csv = std::fs::read_to_string(input)
+            .map_err(|e| format!("Failed to read {}: {e}", input.display()))?;
+        check(input, &csv)?;
+        let results = parse_results(&csv)?;
+        println!("{}: {} tests", input.display(), results.len());
+        runs.push(Run {
+            name: input.file_name().map_or_else(
+                || input.display().to_string(),
+                |name| name.to_string_lossy().to_string(),
+            ),
+            results,
+        });
+    }
+
+    let bytes = preprocessed::encode(&runs)?;
+    std::fs::write(&output, &bytes)
+        .map_err(|e| format!("Failed to write {}: {e}", output.display()))?;
+    println!("Wrote {} ({} bytes)", output.display(), bytes.len());
+    Ok(())
+}
+
+// The viewer leaves out rows without a known status, here they are errors so a typo or a
+// new deqp-runner status cannot silently drop tests. Only the first row may be a header, with
+// `status` as its second column.
+fn check(path: &Path, csv: &str) -> Result<(), String> {
+    let mut reader = csv::ReaderBuilder::new()
+        .has_headers(false)
+        .from_reader(csv.as_bytes());
+    let mut invalid = Vec::new();
+    for (i, record) in reader.records().enumerate() {
+        let record = record.map_err(|e| format!("{}: {e}", path.display()))?;
+        let status = record.get(1).unwrap_or_default();
+        let header = i == 0 && status.eq_ignore_ascii_case("status");
+        if !header && TestStatus::from_str(status).is_err() {
+            let line = record.position().map_or(i as u64 + 1, |p| p.line());
+            invalid.push(format!(
+                "{}:{line}: unknown status `{status}`",
+                path.display()
+            ));
+        }
+    }
+
+    if invalid.is_empty() {
+        return Ok(());
+    }
+    for row in invalid.iter().take(MAX_REPORTED_ROWS) {
+        eprintln!("{row}");
+    }
+    if invalid.len() > MAX_REPORTED_ROWS {
+        eprintln!("... and {} more", invalid.len() - MAX_REPORTED_ROWS);
+    }
+    Err(format!(
+        "{} rows of {} do not hold a known status",
+        invalid.len(),
+        path.display()
+    ))
+}
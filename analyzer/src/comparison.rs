@@ -0,0 +1,128 @@
+//! Test by test changes between two runs
+
+use crate::model::{TestResults, TestStatus};
+use std::collections::{HashMap, HashSet};
+
+/// How a test changed from the base run to the head run
+#[derive(
+    Debug, Eq, Hash, Clone, Copy, PartialEq, strum::EnumCount, strum::EnumIter, strum::Display,
+)]
+pub enum Change {
+    Regression,
+    Fix,
+    #[strum(to_string = "New test")]
+    New,
+    #[strum(to_string = "Removed test")]
+    Removed,
+    Unchanged,
+}
+
+impl Change {
+    pub const fn emoji(&self) -> &'static str {
+        match self {
+            Change::Regression => "📉",
+            Change::Fix => "🔧",
+            Change::New => "🆕",
+            Change::Removed => "🗑️",
+            Change::Unchanged => "➖",
+        }
+    }
+
+    pub const fn color(&self) -> &'static str {
+        match self {
+            Change::Regression => "#ff6467",
+            Change::Fix => "#22c55e",
+            Change::New => "#38bdf8",
+            Change::Removed => "#F77600",
+            Change::Unchanged => "#94a3b8",
+        }
+    }
+
+    // A status change that doesn't cross the failure boundary (e.g. Fail -> Crash)
+    // is not a regression nor a fix
+    pub fn classify(base: Option<TestStatus>, head: Option<TestStatus>) -> Self {
+        match (base, head) {
+            (None, _) => Change::New,
+            (_, None) => Change::Removed,
+            (Some(base), Some(head)) if !base.is_failure() && head.is_failure() => {
+                Change::Regression
+            }
+            (Some(base), Some(head)) if base.is_failure() && !head.is_failure() => Change::Fix,
+            _ => Change::Unchanged,
+        }
+    }
+}
+
+/// A test in either of two runs
+#[derive(Debug, Clone, PartialEq)]
+pub struct Comparison {
+    pub name: String,
+    pub base: Option<TestStatus>,
+    pub head: Option<TestStatus>,
+    pub change: Change,
+}
+
+/// Tests are listed in head order, tests that only exist in the base run come last
+pub fn compare(base: &TestResults, head: &TestResults) -> Vec<Comparison> {
+    let base_statuses = base
+        .iter()
+        .map(|test| (test.name, test.status))
+        .collect::<HashMap<_, _>>();
+    let head_names = head.iter().map(|test| test.name).collect::<HashSet<_>>();
+
+    let mut out = Vec::with_capacity(head.len());
+    for test in head.iter() {
+        let base = base_statuses.get(test.name).copied();
+        let head = Some(test.status);
+        out.push(Comparison {
+            name: test.name.to_string(),
+            base,
+            head,
+            change: Change::classify(base, head),
+        });
+    }
+    for test in base.iter().filter(|t| !head_names.contains(t.name)) {
+        out.push(Comparison {
+            name: test.name.to_string(),
+            base: Some(test.status),
+            head: None,
+            change: Change::Removed,
+        });
+    }
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::model::parse_results;
+
+    #[test]
+    fn classify() {
+        use TestStatus::*;
+        assert_eq!(Change::classify(Some(Pass), Some(Fail)), Change::Regression);
+        assert_eq!(Change::classify(Some(Crash), Some(Pass)), Change::Fix);
+        assert_eq!(Change::classify(Some(Fail), Some(Crash)), Change::Unchanged);
+        assert_eq!(Change::classify(None, Some(Fail)), Change::New);
+        assert_eq!(Change::classify(Some(Pass), None), Change::Removed);
+    }
+
+    #[test]
+    fn removed_tests_come_last() {
+        let base = parse_results("a,Pass,\nb,Pass,\nc,Fail,\n").unwrap();
+        let head = parse_results("c,Pass,\nd,Pass,\na,Fail,\n").unwrap();
+        let changes = compare(&base, &head)
+            .into_iter()
+            .map(|c| (c.name, c.change))
+            .collect::<Vec<_>>();
+        assert_eq!(
+            changes,
+            [
+                ("c".to_string(), Change::Fix),
+                ("d".to_string(), Change::New),
+                ("a".to_string(), Change::Regression),
+                ("b".to_string(), Change::Removed),
+            ]
+        );
+    }
+}
@@ -0,0 +1,474 @@
+//! Statuses a run is expected to give, from a baseline run or deqp-runner's fails, flakes and
+//! skips lists, and the results that do not match them
+
+use crate::model::{TestResults, TestStatus};
+use regex::{Regex, RegexSet};
+use std::collections::{HashMap, HashSet};
+use std::fmt;
+use std::str::FromStr;
+
+/// Statuses that fail a gated run when they are not expected
+pub const GATED_STATUSES: [TestStatus; 3] =
+    [TestStatus::Fail, TestStatus::Crash, TestStatus::Timeout];
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct ExpectationsError(String);
+
+impl fmt::Display for ExpectationsError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}", self.0)
+    }
+}
+
+impl std::error::Error for ExpectationsError {}
+
+/// The lists of expected outcomes kept by Mesa-style CI next to its results, e.g.
+/// `radv-navi21-fails.txt`
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, strum::Display)]
+#[strum(serialize_all = "lowercase")]
+pub enum ExpectationsFile {
+    /// A `name,Status` line per expected failure, as taken by deqp-runner's `--baseline`
+    Fails,
+    /// A regex per line, matching tests whose outcome does not matter
+    Flakes,
+    /// A regex per line, matching tests that are not run
+    Skips,
+}
+
+impl ExpectationsFile {
+    pub fn from_file_name(name: &str) -> Option<Self> {
+        [Self::Fails, Self::Flakes, Self::Skips]
+            .into_iter()
+            .find(|file| name.ends_with(&format!("{file}.txt")))
+    }
+}
+
+// Lines of a list that are neither blank nor `#` comments, with their line number
+fn lines(text: &str) -> impl Iterator<Item = (usize, &str)> {
+    text.lines()
+        .enumerate()
+        .map(|(i, line)| (i + 1, line.trim()))
+        .filter(|(_, line)| !is_comment(line))
+}
+
+fn is_comment(line: &str) -> bool {
+    let line = line.trim();
+    line.is_empty() || line.starts_with('#')
+}
+
+// A `name,Status` line of a fails list
+fn parse_fail(line: usize, entry: &str) -> Result<(&str, TestStatus), ExpectationsError> {
+    let (name, status) = entry
+        .rsplit_once(',')
+        .ok_or_else(|| ExpectationsError(format!("line {line}: expected `name,Status`")))?;
+    let status = TestStatus::from_str(status.trim()).map_err(|_| {
+        ExpectationsError(format!("line {line}: unknown status `{}`", status.trim()))
+    })?;
+    Ok((name.trim(), status))
+}
+
+fn parse_patterns(text: &str) -> Result<RegexSet, ExpectationsError> {
+    let mut patterns = Vec::new();
+    for (line, pattern) in lines(text) {
+        Regex::new(pattern)
+            .map_err(|e| ExpectationsError(format!("line {line}: invalid regex: {e}")))?;
+        patterns.push(pattern);
+    }
+    RegexSet::new(patterns).map_err(|e| ExpectationsError(e.to_string()))
+}
+
+/// Expected outcome of each test: tests that are not listed are expected to pass, tests matching
+/// a flake may give anything
+#[derive(Debug, Clone, Default)]
+pub struct Expectations {
+    statuses: HashMap<String, TestStatus>,
+    flakes: RegexSet,
+    skips: RegexSet,
+    // The loaded lists as written, so exporting them keeps their comments and order
+    texts: HashMap<ExpectationsFile, String>,
+}
+
+impl Expectations {
+    /// Every status of a previous run is expected again, so only new failures are unexpected
+    pub fn from_baseline(results: &TestResults) -> Self {
+        Self {
+            statuses: results
+                .iter()
+                .map(|test| (test.name.to_string(), test.status))
+                .collect(),
+            ..Self::default()
+        }
+    }
+
+    /// Expectations of a fails list, see [`ExpectationsFile::Fails`]
+    pub fn parse(text: &str) -> Result<Self, ExpectationsError> {
+        let mut expectations = Self::default();
+        expectations.load(ExpectationsFile::Fails, text)?;
+        Ok(expectations)
+    }
+
+    /// Replaces one of the lists, blank lines and lines starting with `#` are ignored
+    pub fn load(&mut self, file: ExpectationsFile, text: &str) -> Result<(), ExpectationsError> {
+        match file {
+            ExpectationsFile::Fails => {
+                let mut statuses = HashMap::new();
+                for (line, entry) in lines(text) {
+                    let (name, status) = parse_fail(line, entry)?;
+                    statuses.insert(name.to_string(), status);
+                }
+                self.statuses = statuses;
+            }
+            ExpectationsFile::Flakes => self.flakes = parse_patterns(text)?,
+            ExpectationsFile::Skips => self.skips = parse_patterns(text)?,
+        }
+        self.texts.insert(file, text.to_string());
+        Ok(())
+    }
+
+    /// Number of listed statuses
+    pub fn len(&self) -> usize {
+        self.statuses.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.statuses.is_empty() && self.flakes.is_empty() && self.skips.is_empty()
+    }
+
+    pub fn expected(&self, name: &str) -> Option<TestStatus> {
+        self.statuses.get(name).copied()
+    }
+
+    pub fn is_flaky(&self, name: &str) -> bool {
+        self.flakes.is_match(name)
+    }
+
+    pub fn is_skipped(&self, name: &str) -> bool {
+        self.skips.is_match(name)
+    }
+
+    // Same rules as deqp-runner: a test failing another way than listed (e.g. Crash instead of
+    // Fail) is unexpected, and so is a listed failure that now passes
+    pub fn is_expected(&self, name: &str, status: TestStatus) -> bool {
+        if self.is_flaky(name) {
+            return true;
+        }
+        let listed = self.expected(name);
+        match status {
+            TestStatus::Pass | TestStatus::Warn => !listed.is_some_and(|s| s.is_failure()),
+            // Already sorted out by the runner
+            TestStatus::Skip | TestStatus::ExpectedFail | TestStatus::KnownFlake => true,
+            TestStatus::Missing => self.is_skipped(name) || listed == Some(status),
+            _ => listed == Some(status),
+        }
+    }
+
+    /// A gated status the lists do not account for, what both the `gate` command and the viewer
+    /// report as unexpected
+    pub fn is_unexpected(&self, name: &str, status: TestStatus) -> bool {
+        GATED_STATUSES.contains(&status) && !self.is_expected(name, status)
+    }
+
+    // Status a test gets in the fails list: its Fail, Crash or Timeout when it is not flaky, an
+    // expected failure keeps its listed status
+    fn failure(&self, name: &str, status: TestStatus) -> Option<TestStatus> {
+        if self.is_flaky(name) {
+            return None;
+        }
+        match status {
+            TestStatus::ExpectedFail => Some(
+                self.expected(name)
+                    .filter(|s| GATED_STATUSES.contains(s))
+                    .unwrap_or(TestStatus::Fail),
+            ),
+            status if GATED_STATUSES.contains(&status) => Some(status),
+            _ => None,
+        }
+    }
+
+    /// One of the lists updated from a run, in deqp-runner's format. The loaded list is kept as
+    /// written, comments and order included, and the new entries come after it in run order:
+    /// - fails: listed tests get their Fail, Crash or Timeout status in the run and are dropped once
+    ///   they pass, the others (not run, missing, skipped or flaky) are kept, then the new failures
+    /// - flakes: the current patterns, then the tests that flaked
+    /// - skips: the current patterns, then the skipped tests
+    ///
+    /// With `collapse`, new flakes and skips are merged into a pattern per group when every test
+    /// of the group in the run is listed. The fails list only takes test names, see
+    /// [`Expectations::fail_patterns`] for its collapsed form.
+    pub fn export(&self, file: ExpectationsFile, results: &TestResults, collapse: bool) -> String {
+        let text = self.texts.get(&file).map_or("", String::as_str);
+        let mut lines = Vec::new();
+        match file {
+            ExpectationsFile::Fails => {
+                let failures = results
+                    .iter()
+                    .filter_map(|test| Some((test.name, self.failure(test.name, test.status)?)))
+                    .collect::<Vec<_>>();
+                let statuses = failures.iter().copied().collect::<HashMap<_, _>>();
+                // A missing or skipped test does not show its failure is fixed
+                let passed = results
+                    .iter()
+                    .filter(|test| {
+                        matches!(
+                            test.status,
+                            TestStatus::Pass | TestStatus::Warn | TestStatus::UnexpectedPass
+                        )
+                    })
+                    .map(|test| test.name)
+                    .collect::<HashSet<_>>();
+                let mut written = HashSet::new();
+                for (i, line) in text.lines().enumerate() {
+                    let entry = if is_comment(line) {
+                        None
+                    } else {
+                        parse_fail(i + 1, line.trim()).ok()
+                    };
+                    let Some((name, _)) = entry else {
+                        lines.push(line.to_string());
+                        continue;
+                    };
+                    if let Some(status) = statuses.get(name) {
+                        if written.insert(name) {
+                            lines.push(format!("{name},{status}"));
+                        }
+                    } else if !passed.contains(name) {
+                        lines.push(line.to_string());
+                    }
+                }
+                lines.extend(
+                    failures
+                        .iter()
+                        .filter(|(name, _)| !written.contains(name))
+                        .map(|(name, status)| format!("{name},{status}")),
+                );
+            }
+            ExpectationsFile::Flakes | ExpectationsFile::Skips => {
+                let (current, statuses) = match file {
+                    ExpectationsFile::Flakes => (
+                        &self.flakes,
+                        [TestStatus::Flake, TestStatus::KnownFlake].as_slice(),
+                    ),
+                    _ => (&self.skips, [TestStatus::Skip].as_slice()),
+                };
+                let listed = results
+                    .iter()
+                    .filter(|test| statuses.contains(&test.status) && !current.is_match(test.name))
+                    .map(|test| test.name)
+                    .collect::<HashSet<_>>();
+                lines.extend(text.lines().map(str::to_string));
+                lines.extend(patterns(results, &listed, collapse));
+            }
+        }
+        lines.into_iter().map(|line| line + "\n").collect()
+    }
+
+    /// Regexes matching the failures of the exported fails list, one per line, e.g. to rerun
+    /// them. With `collapse`, a group whose every test in the run fails is a single pattern.
+    pub fn fail_patterns(&self, results: &TestResults, collapse: bool) -> String {
+        let listed = results
+            .iter()
+            .filter(|test| self.failure(test.name, test.status).is_some())
+            .map(|test| test.name)
+            .collect::<HashSet<_>>();
+        patterns(results, &listed, collapse)
+            .into_iter()
+            .map(|line| line + "\n")
+            .collect()
+    }
+}
+
+// Test names, or group prefixes ending with a dot, as anchored regexes
+fn literal(name: &str) -> String {
+    format!("^{}", regex::escape(name))
+}
+
+// Patterns matching the `listed` tests in run order. With `collapse`, a test is matched by its
+// outermost group of at least two tests whose every test is listed.
+fn patterns(results: &TestResults, listed: &HashSet<&str>, collapse: bool) -> Vec<String> {
+    let groups = |name: &str| {
+        name.match_indices('.')
+            .map(|(i, _)| name[..=i].to_string())
+            .collect::<Vec<_>>()
+    };
+
+    // Tests and listed tests under each group
+    let mut counts = HashMap::<String, (usize, usize)>::new();
+    if collapse {
+        for test in results.iter() {
+            for group in groups(test.name) {
+                let (tests, listed_tests) = counts.entry(group).or_default();
+                *tests += 1;
+                *listed_tests += usize::from(listed.contains(test.name));
+            }
+        }
+    }
+
+    let mut seen = HashSet::new();
+    results
+        .iter()
+        .filter(|test| listed.contains(test.name))
+        .map(|test| {
+            groups(test.name)
+                .into_iter()
+                .find(|group| counts.get(group).is_some_and(|(t, l)| t == l && *t >= 2))
+                .map(|group| literal(&group))
+                .unwrap_or_else(|| literal(test.name) + "$")
+        })
+        .filter(|pattern| seen.insert(pattern.clone()))
+        .collect()
+}
+
+/// A result that does not match its expectation
+#[derive(Debug, Clone, PartialEq, serde::Serialize)]
+pub struct Unexpected<'a> {
+    pub name: &'a str,
+    pub status: TestStatus,
+    pub expected: Option<TestStatus>,
+}
+
+/// Results with a gated status they are not expected to have, in run order
+pub fn unexpected<'a>(
+    results: &'a TestResults,
+    expectations: &Expectations,
+) -> Vec<Unexpected<'a>> {
+    results
+        .iter()
+        .filter(|test| expectations.is_unexpected(test.name, test.status))
+        .map(|test| Unexpected {
+            name: test.name,
+            status: test.status,
+            expected: expectations.expected(test.name),
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::model::parse_results;
+
+    #[test]
+    fn parse() {
+        let expectations =
+            Expectations::parse("# known\n\ndEQP-VK.a,Fail\ndEQP-VK.b, Crash\n").unwrap();
+        assert_eq!(expectations.len(), 2);
+        assert_eq!(expectations.expected("dEQP-VK.b"), Some(TestStatus::Crash));
+        assert_eq!(expectations.expected("dEQP-VK.c"), None);
+        assert!(Expectations::parse("dEQP-VK.a").is_err());
+        assert!(Expectations::parse("dEQP-VK.a,Broken").is_err());
+    }
+
+    #[test]
+    fn file_names() {
+        assert_eq!(
+            ExpectationsFile::from_file_name("radv-navi21-fails.txt"),
+            Some(ExpectationsFile::Fails)
+        );
+        assert_eq!(
+            ExpectationsFile::from_file_name("flakes.txt"),
+            Some(ExpectationsFile::Flakes)
+        );
+        assert_eq!(ExpectationsFile::from_file_name("results.csv"), None);
+    }
+
+    #[test]
+    fn lists() {
+        let mut expectations = Expectations::parse("a.fail,Fail\na.crash,Crash\n").unwrap();
+        expectations
+            .load(ExpectationsFile::Flakes, "# flaky\ndEQP-VK.wsi.*\n")
+            .unwrap();
+        expectations
+            .load(ExpectationsFile::Skips, "^a\\.slow\\.")
+            .unwrap();
+        assert!(expectations.is_expected("a.fail", TestStatus::Fail));
+        assert!(!expectations.is_expected("a.fail", TestStatus::Crash));
+        assert!(!expectations.is_expected("a.fail", TestStatus::Pass));
+        assert!(expectations.is_expected("a.other", TestStatus::Pass));
+        assert!(!expectations.is_expected("a.other", TestStatus::Timeout));
+        assert!(expectations.is_expected("dEQP-VK.wsi.x.y", TestStatus::Crash));
+        assert!(expectations.is_expected("a.slow.x", TestStatus::Missing));
+        assert!(!expectations.is_expected("a.other", TestStatus::Missing));
+        assert!(
+            expectations
+                .load(ExpectationsFile::Flakes, "ok\n(unclosed")
+                .is_err()
+        );
+    }
+
+    #[test]
+    fn export_fails() {
+        let expectations = Expectations::parse(
+            "# gpu hangs\nx.gone,Timeout\nx.a,Crash\n\nx.b,Fail\nx.e,Fail\nx.f,Crash\n",
+        )
+        .unwrap();
+        let run = parse_results(
+            "x.a,ExpectedFail,\nx.b,Pass,\nx.c,Timeout,\nx.d,Flake,\nx.e,Missing,\nx.f,Skip,\n",
+        )
+        .unwrap();
+        assert_eq!(
+            expectations.export(ExpectationsFile::Fails, &run, false),
+            "# gpu hangs\nx.gone,Timeout\nx.a,Crash\n\nx.e,Fail\nx.f,Crash\nx.c,Timeout\n"
+        );
+        assert_eq!(
+            Expectations::default().export(ExpectationsFile::Fails, &run, false),
+            "x.a,Fail\nx.c,Timeout\n"
+        );
+    }
+
+    #[test]
+    fn fail_patterns_collapse_failing_siblings() {
+        let run = parse_results("g.a.1,Fail,\ng.a.2,Crash,\ng.b.1,Fail,\ng.b.2,Pass,\n").unwrap();
+        let expectations = Expectations::default();
+        assert_eq!(
+            expectations.fail_patterns(&run, false),
+            "^g\\.a\\.1$\n^g\\.a\\.2$\n^g\\.b\\.1$\n"
+        );
+        assert_eq!(
+            expectations.fail_patterns(&run, true),
+            "^g\\.a\\.\n^g\\.b\\.1$\n"
+        );
+    }
+
+    #[test]
+    fn export_collapses_siblings() {
+        let mut expectations = Expectations::default();
+        expectations
+            .load(ExpectationsFile::Skips, "# slow\n^old\\.")
+            .unwrap();
+        let run = parse_results(
+            "g.a.1,Skip,\ng.a.2,Skip,\ng.b.1,Skip,\ng.b.2,Pass,\nold.1,Skip,\ng.c.x+y,Skip,\n",
+        )
+        .unwrap();
+        assert_eq!(
+            expectations.export(ExpectationsFile::Skips, &run, false),
+            "# slow\n^old\\.\n^g\\.a\\.1$\n^g\\.a\\.2$\n^g\\.b\\.1$\n^g\\.c\\.x\\+y$\n"
+        );
+        assert_eq!(
+            expectations.export(ExpectationsFile::Skips, &run, true),
+            "# slow\n^old\\.\n^g\\.a\\.\n^g\\.b\\.1$\n^g\\.c\\.x\\+y$\n"
+        );
+        let exported = expectations.export(ExpectationsFile::Skips, &run, true);
+        let mut reloaded = Expectations::default();
+        reloaded.load(ExpectationsFile::Skips, &exported).unwrap();
+        assert!(reloaded.is_skipped("g.a.3"));
+        assert!(!reloaded.is_skipped("g.b.2"));
+    }
+
+    #[test]
+    fn only_new_gated_statuses_are_unexpected() {
+        let baseline = parse_results("a,Fail,\nb,Pass,\nc,Pass,\nd,Fail,\n").unwrap();
+        let run = parse_results("a,Fail,\nb,Timeout,\nc,Flake,\nd,Crash,\ne,Fail,\n").unwrap();
+        let unexpected = unexpected(&run, &Expectations::from_baseline(&baseline))
+            .into_iter()
+            .map(|test| (test.name, test.expected))
+            .collect::<Vec<_>>();
+        assert_eq!(
+            unexpected,
+            [
+                ("b", Some(TestStatus::Pass)),
+                ("d", Some(TestStatus::Fail)),
+                ("e", None),
+            ]
+        );
+    }
+}
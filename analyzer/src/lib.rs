@@ -0,0 +1,11 @@
+//! Results model shared by the viewer and its command-line tools, free of any UI code: reading
+//! results archives, parsing, search queries, sorting, statistics, comparison of runs and expectations
+
+pub mod archive;
+pub mod comparison;
+pub mod expectations;
+pub mod model;
+pub mod preprocessed;
+pub mod query;
+pub mod sort;
+pub mod stats;
@@ -0,0 +1,357 @@
+//! Headless summary of results, the viewer's figures in a terminal or as JSON for scripts:
+//! `cargo run -p vulkan-cts-analyzer -- assets/results.zip --json`.
+//! The `gate` mode checks a run against a baseline for CI: `-- gate results.csv --baseline previous.csv`.
+
+use futures_lite::future::block_on;
+use serde::Serialize;
+use std::error::Error;
+use std::io::IsTerminal;
+use std::path::{Path, PathBuf};
+use std::process::ExitCode;
+use std::time::Duration;
+use strum::IntoEnumIterator;
+use vulkan_cts_analyzer::archive::Archive;
+use vulkan_cts_analyzer::expectations::{self, Expectations, ExpectationsFile, GATED_STATUSES};
+use vulkan_cts_analyzer::model::{HMSDuration, TestStatus, pass_rate};
+use vulkan_cts_analyzer::preprocessed::Run;
+use vulkan_cts_analyzer::stats::{self, GroupFailures};
+
+const USAGE: &str =
+    "Usage: vulkan-cts-analyzer [RESULTS...] [--json] [--slowest N] [--groups N] [--depth N]
+
+Summarizes every run of results CSVs, zip archives or preprocessed binaries
+(assets/results.csv by default): status counts, pass rate, slowest tests and the
+groups with the most failures.
+
+Options:
+  --json         Print the summaries as JSON
+  --slowest N    Slowest tests listed (10 by default)
+  --groups N     Failing groups listed (10 by default)
+  --depth N      Name segments of a group, e.g. 3 for dEQP-VK.api.smoke (3 by default)
+
+Run `vulkan-cts-analyzer gate --help` to check a run against a baseline.";
+
+const GATE_USAGE: &str = "Usage: vulkan-cts-analyzer gate RESULTS (--baseline RESULTS | --expectations FILE) [--flakes FILE] [-o OUTPUT]
+
+Exits with 1 when the run has Fail, Crash or Timeout results that are not expected,
+0 when it has none and 2 on errors.
+
+Options:
+  --baseline RESULTS   Previous run, its statuses are expected again
+  --expectations FILE  Expected statuses, a `name,Status` line per test as in
+                       deqp-runner's --baseline (tests not listed must pass)
+  --flakes FILE        Regexes of tests whose outcome does not matter, one per line
+  -o, --output FILE    Write the unexpected results as JSON";
+
+const DEFAULT_INPUT: &str = "assets/results.csv";
+
+// Unexpected results printed before the rest are summarized, the output file has them all
+const MAX_LISTED_UNEXPECTED: usize = 50;
+
+// The exit code of errors, 1 is for runs that fail the gate
+const ERROR: u8 = 2;
+
+struct Options {
+    inputs: Vec<PathBuf>,
+    json: bool,
+    slowest: usize,
+    groups: usize,
+    depth: usize,
+}
+
+#[derive(Serialize)]
+struct StatusCount {
+    status: TestStatus,
+    count: usize,
+}
+
+#[derive(Serialize)]
+struct SlowTest<'a> {
+    name: &'a str,
+    status: TestStatus,
+    seconds: f32,
+}
+
+#[derive(Serialize)]
+struct Summary<'a> {
+    run: &'a str,
+    tests: usize,
+    pass_rate: Option<f32>,
+    counts: Vec<StatusCount>,
+    slowest: Vec<SlowTest<'a>>,
+    failing_groups: Vec<GroupFailures>,
+}
+
+impl<'a> Summary<'a> {
+    fn new(run: &'a Run, options: &Options) -> Self {
+        let results = &run.results;
+        let counts = results.status_counts();
+        Self {
+            run: &run.name,
+            tests: results.len(),
+            pass_rate: pass_rate(counts.iter().map(|(s, c)| (*s, *c))),
+            counts: TestStatus::iter()
+                .filter_map(|status| {
+                    let count = counts.get(&status).copied().unwrap_or_default();
+                    (count > 0).then_some(StatusCount { status, count })
+                })
+                .collect(),
+            slowest: stats::slowest(results, options.slowest)
+                .into_iter()
+                .map(|i| SlowTest {
+                    name: results.name(i),
+                    status: results.status(i),
+                    seconds: results.seconds(i).unwrap_or_default(),
+                })
+                .collect(),
+            failing_groups: stats::failing_groups(results, options.depth, options.groups),
+        }
+    }
+}
+
+struct GateOptions {
+    input: PathBuf,
+    baseline: Option<PathBuf>,
+    expectations: Option<PathBuf>,
+    flakes: Option<PathBuf>,
+    output: Option<PathBuf>,
+}
+
+fn main() -> ExitCode {
+    let mut args = std::env::args().skip(1).peekable();
+    let result = if args.next_if(|arg| arg == "gate").is_some() {
+        gate(args)
+    } else {
+        summarize(args).map(|()| ExitCode::SUCCESS)
+    };
+    result.unwrap_or_else(|e| {
+        eprintln!("error: {e}");
+        ExitCode::from(ERROR)
+    })
+}
+
+fn summarize(args: impl Iterator<Item = String>) -> Result<(), Box<dyn Error>> {
+    let Some(options) = parse_args(args)? else {
+        println!("{USAGE}");
+        return Ok(());
+    };
+
+    let mut runs = Vec::new();
+    for input in &options.inputs {
+        runs.extend(read_runs(input)?);
+    }
+    let summaries = runs
+        .iter()
+        .map(|run| Summary::new(run, &options))
+        .collect::<Vec<_>>();
+
+    if options.json {
+        println!("{}", serde_json::to_string_pretty(&summaries)?);
+    } else {
+        let color = std::io::stdout().is_terminal() && std::env::var_os("NO_COLOR").is_none();
+        for (i, summary) in summaries.iter().enumerate() {
+            if i > 0 {
+                println!();
+            }
+            print_summary(summary, options.depth, color);
+        }
+    }
+    Ok(())
+}
+
+// `None` when the usage was asked for
+fn parse_args(mut args: impl Iterator<Item = String>) -> Result<Option<Options>, Box<dyn Error>> {
+    let mut options = Options {
+        inputs: Vec::new(),
+        json: false,
+        slowest: 10,
+        groups: 10,
+        depth: 3,
+    };
+    while let Some(arg) = args.next() {
+        let mut number = |name: &str| -> Result<usize, String> {
+            let value = args.next().ok_or(format!("`{name}` needs a number"))?;
+            value
+                .parse()
+                .map_err(|_| format!("`{name}` needs a number, got `{value}`"))
+        };
+        match arg.as_str() {
+            "--json" => options.json = true,
+            "--slowest" => options.slowest = number("--slowest")?,
+            "--groups" => options.groups = number("--groups")?,
+            "--depth" => options.depth = number("--depth")?.max(1),
+            "-h" | "--help" => return Ok(None),
+            _ if arg.starts_with('-') => return Err(format!("Unknown option `{arg}`").into()),
+            _ => options.inputs.push(PathBuf::from(arg)),
+        }
+    }
+    if options.inputs.is_empty() {
+        options.inputs.push(PathBuf::from(DEFAULT_INPUT));
+    }
+    Ok(Some(options))
+}
+
+fn gate(args: impl Iterator<Item = String>) -> Result<ExitCode, Box<dyn Error>> {
+    let Some(options) = parse_gate_args(args)? else {
+        println!("{GATE_USAGE}");
+        return Ok(ExitCode::SUCCESS);
+    };
+
+    let run = read_run(&options.input)?;
+    let mut expectations = match (&options.baseline, &options.expectations) {
+        (Some(baseline), None) => Expectations::from_baseline(&read_run(baseline)?.results),
+        (None, Some(path)) => Expectations::parse(&read_text(path)?)
+            .map_err(|e| format!("{}: {e}", path.display()))?,
+        _ => return Err("`gate` needs either `--baseline` or `--expectations`".into()),
+    };
+    if let Some(path) = &options.flakes {
+        expectations
+            .load(ExpectationsFile::Flakes, &read_text(path)?)
+            .map_err(|e| format!("{}: {e}", path.display()))?;
+    }
+
+    let unexpected = expectations::unexpected(&run.results, &expectations);
+    if let Some(output) = &options.output {
+        std::fs::write(output, serde_json::to_string_pretty(&unexpected)?)
+            .map_err(|e| format!("Failed to write {}: {e}", output.display()))?;
+    }
+
+    let gated = GATED_STATUSES.map(|s| s.to_string()).join("/");
+    if unexpected.is_empty() {
+        println!(
+            "{}: no unexpected {gated} in {} tests",
+            run.name,
+            run.results.len()
+        );
+        return Ok(ExitCode::SUCCESS);
+    }
+    println!(
+        "{}: {} unexpected {gated} in {} tests",
+        run.name,
+        unexpected.len(),
+        run.results.len()
+    );
+    for test in unexpected.iter().take(MAX_LISTED_UNEXPECTED) {
+        match test.expected {
+            Some(expected) => println!("  {},{} (expected {expected})", test.name, test.status),
+            None => println!("  {},{}", test.name, test.status),
+        }
+    }
+    if unexpected.len() > MAX_LISTED_UNEXPECTED {
+        println!(
+            "  ... and {} more",
+            unexpected.len() - MAX_LISTED_UNEXPECTED
+        );
+    }
+    Ok(ExitCode::FAILURE)
+}
+
+// `None` when the usage was asked for
+fn parse_gate_args(
+    mut args: impl Iterator<Item = String>,
+) -> Result<Option<GateOptions>, Box<dyn Error>> {
+    let mut input = None;
+    let mut options = GateOptions {
+        input: PathBuf::new(),
+        baseline: None,
+        expectations: None,
+        flakes: None,
+        output: None,
+    };
+    while let Some(arg) = args.next() {
+        let mut path = |name: &str| -> Result<Option<PathBuf>, String> {
+            args.next()
+                .map(|path| Some(PathBuf::from(path)))
+                .ok_or(format!("`{name}` needs a path"))
+        };
+        match arg.as_str() {
+            "--baseline" => options.baseline = path("--baseline")?,
+            "--expectations" => options.expectations = path("--expectations")?,
+            "--flakes" => options.flakes = path("--flakes")?,
+            "-o" | "--output" => options.output = path("--output")?,
+            "-h" | "--help" => return Ok(None),
+            _ if arg.starts_with('-') => return Err(format!("Unknown option `{arg}`").into()),
+            _ if input.is_some() => return Err("`gate` checks a single run".into()),
+            _ => input = Some(PathBuf::from(arg)),
+        }
+    }
+    options.input = input.ok_or("`gate` needs the results to check")?;
+    Ok(Some(options))
+}
+
+fn read_text(path: &Path) -> Result<String, String> {
+    std::fs::read_to_string(path).map_err(|e| format!("Failed to read {}: {e}", path.display()))
+}
+
+/// The run of a file holding exactly one
+fn read_run(path: &Path) -> Result<Run, Box<dyn Error>> {
+    let mut runs = read_runs(path)?;
+    match runs.len() {
+        1 => Ok(runs.remove(0)),
+        count => Err(format!("{} holds {count} runs, expected one", path.display()).into()),
+    }
+}
+
+/// Runs of a zip archive or a preprocessed binary, or of a results CSV (a single run named after the file)
+fn read_runs(path: &Path) -> Result<Vec<Run>, Box<dyn Error>> {
+    let bytes =
+        std::fs::read(path).map_err(|e| format!("Failed to read {}: {e}", path.display()))?;
+    let name = path.file_name().map_or_else(
+        || path.display().to_string(),
+        |name| name.to_string_lossy().to_string(),
+    );
+    // Everything is in memory, the futures never wait on anything
+    let archive = block_on(Archive::open(&name, bytes))?;
+    let mut runs = Vec::with_capacity(archive.runs.len());
+    for (i, name) in archive.runs.iter().enumerate() {
+        runs.push(Run {
+            name: name.clone(),
+            results: block_on(archive.results(i))?,
+        });
+    }
+    Ok(runs)
+}
+
+// Foreground in the status color, e.g. `#22c55e`
+fn paint(text: &str, hex: &str, color: bool) -> String {
+    let channel = |i: usize| u8::from_str_radix(hex.get(i..i + 2)?, 16).ok();
+    match (color, channel(1), channel(3), channel(5)) {
+        (true, Some(r), Some(g), Some(b)) => format!("\x1b[38;2;{r};{g};{b}m{text}\x1b[0m"),
+        _ => text.to_string(),
+    }
+}
+
+fn print_summary(summary: &Summary, depth: usize, color: bool) {
+    print!("{}: {} tests", summary.run, summary.tests);
+    match summary.pass_rate {
+        Some(rate) => println!(", {rate:.2}% pass rate"),
+        None => println!(),
+    }
+    for StatusCount { status, count } in &summary.counts {
+        let name = paint(
+            &format!("{:<14}", status.to_string()),
+            status.color(),
+            color,
+        );
+        let share = *count as f32 * 100.0 / summary.tests as f32;
+        println!("  {name}{count:>9}  {share:>6.2}%");
+    }
+
+    if !summary.slowest.is_empty() {
+        println!("\nSlowest tests");
+        for test in &summary.slowest {
+            let duration = Duration::try_from_secs_f32(test.seconds)
+                .map_or_else(|_| "-".to_string(), |d| HMSDuration(d).to_string());
+            let status = paint(&test.status.to_string(), test.status.color(), color);
+            println!("  {duration:>12}  {}  {status}", test.name);
+        }
+    }
+
+    if !summary.failing_groups.is_empty() {
+        println!("\nMost failing groups (depth {depth})");
+        for group in &summary.failing_groups {
+            let failures = format!("{:>7} / {:<7}", group.failures, group.tests);
+            println!("  {failures}  {}", group.group);
+        }
+    }
+}
@@ -0,0 +1,524 @@
+use csv::{ReaderBuilder, StringRecord};
+use std::collections::HashMap;
+use std::fmt;
+use std::ops::Range;
+use std::str::FromStr;
+use std::time::Duration;
+use strum::{EnumCount, IntoEnumIterator};
+
+#[derive(
+    Debug,
+    Eq,
+    Hash,
+    Clone,
+    Copy,
+    PartialEq,
+    strum::EnumCount,
+    strum::EnumIter,
+    strum::Display,
+    strum::EnumString,
+    serde::Serialize,
+    serde::Deserialize,
+)]
+pub enum TestStatus {
+    Pass,
+    Fail,
+    Warn,
+    Skip,
+    Crash,
+    Timeout,
+    Flake,
+    ExpectedFail,
+    UnexpectedPass,
+    KnownFlake,
+    Missing,
+}
+
+impl TestStatus {
+    pub const fn emoji(&self) -> &'static str {
+        match self {
+            TestStatus::Pass => "✅",
+            TestStatus::Fail => "❌",
+            TestStatus::Skip => "❎",
+            TestStatus::Timeout => "⏱️",
+            TestStatus::Warn => "⚠️",
+            TestStatus::Crash => "💥",
+            TestStatus::Flake => "🎲",
+            TestStatus::ExpectedFail => "🔕",
+            TestStatus::UnexpectedPass => "🎉",
+            TestStatus::KnownFlake => "♻️",
+            TestStatus::Missing => "❓",
+        }
+    }
+
+    pub const fn color(&self) -> &'static str {
+        match self {
+            TestStatus::Pass => "#22c55e",
+            TestStatus::Fail => "#ff6467",
+            TestStatus::Skip => "#38bdf8",
+            TestStatus::Timeout => "#F77600",
+            TestStatus::Warn => "#ffdf20",
+            TestStatus::Crash => "#e7000b",
+            TestStatus::Flake => "#c084fc",
+            TestStatus::ExpectedFail => "#94a3b8",
+            TestStatus::UnexpectedPass => "#2dd4bf",
+            TestStatus::KnownFlake => "#818cf8",
+            TestStatus::Missing => "#f472b6",
+        }
+    }
+
+    // Same rule as deqp-runner: anything that would make the run fail is a failure.
+    // Flakes and expected failures are already accounted for by the runner.
+    pub const fn is_failure(&self) -> bool {
+        matches!(
+            self,
+            TestStatus::Fail
+                | TestStatus::Crash
+                | TestStatus::Timeout
+                | TestStatus::Missing
+                | TestStatus::UnexpectedPass
+        )
+    }
+}
+
+/// Share of successes among the tests that ran, skipped tests are left out like in the pie chart
+pub fn pass_rate(counts: impl IntoIterator<Item = (TestStatus, usize)>) -> Option<f32> {
+    let (ran, failed) = counts
+        .into_iter()
+        .filter(|(s, _)| *s != TestStatus::Skip)
+        .fold((0_usize, 0_usize), |(ran, failed), (s, v)| {
+            (ran + v, if s.is_failure() { failed + v } else { failed })
+        });
+    (ran > 0).then(|| 100.0 - (failed as f32 * 100.0) / ran as f32)
+}
+
+pub fn failure_statuses() -> String {
+    TestStatus::iter()
+        .filter(TestStatus::is_failure)
+        .map(|s| s.to_string())
+        .collect::<Vec<_>>()
+        .join(", ")
+}
+
+/// A test of a run, borrowed from the columns of [`TestResults`]
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct TestResult<'a> {
+    pub name: &'a str,
+    pub status: TestStatus,
+    pub duration: Option<Duration>,
+}
+
+// Wrapper for displaying a duration in h:m:s (integer seconds, rounded down)
+pub struct HMSDuration(pub Duration);
+impl fmt::Display for HMSDuration {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let mut ms = self.0.as_millis();
+        let hours = ms / 3_600_000;
+        ms %= 3_600_000;
+        let mins = ms / 60_000;
+        ms %= 60_000;
+        let secs = ms / 1000;
+        ms %= 1000;
+
+        if hours > 0 {
+            write!(f, "{}:{:02}:{:02}.{}", hours, mins, secs, ms)
+        } else if mins > 0 {
+            write!(f, "0:{}:{:02}.{}", mins, secs, ms)
+        } else {
+            write!(f, "0:0:{}.{}", secs, ms)
+        }
+    }
+}
+
+/// Parsed results of a run, stored column-wise with every name interned in a single buffer
+#[derive(Debug, Clone, Default, serde::Serialize, serde::Deserialize)]
+pub struct TestResults {
+    names: String,
+    // End of each name in `names`
+    name_ends: Vec<u32>,
+    statuses: Vec<TestStatus>,
+    // In seconds, NaN when the CSV holds no valid duration
+    durations: Vec<f32>,
+    // Number of tests per status, indexed by `TestStatus as usize`
+    counts: [usize; TestStatus::COUNT],
+}
+
+// Durations are compared bit for bit, a derived comparison would never find NaN equal to itself
+impl PartialEq for TestResults {
+    fn eq(&self, other: &Self) -> bool {
+        self.names == other.names
+            && self.name_ends == other.name_ends
+            && self.statuses == other.statuses
+            && self.counts == other.counts
+            && self.durations.len() == other.durations.len()
+            && self
+                .durations
+                .iter()
+                .zip(&other.durations)
+                .all(|(a, b)| a.to_bits() == b.to_bits())
+    }
+}
+
+impl TestResults {
+    /// Results parsed elsewhere, e.g. by the results worker, `None` if the columns do not match
+    pub fn from_columns(
+        names: String,
+        name_ends: Vec<u32>,
+        statuses: Vec<TestStatus>,
+        durations: Vec<f32>,
+        counts: [usize; TestStatus::COUNT],
+    ) -> Option<Self> {
+        let results = Self {
+            names,
+            name_ends,
+            statuses,
+            durations,
+            counts,
+        };
+        results.is_consistent().then_some(results)
+    }
+
+    /// The columns taken by [`TestResults::from_columns`], e.g. to send the rows to another thread
+    pub fn into_columns(
+        self,
+    ) -> (
+        String,
+        Vec<u32>,
+        Vec<TestStatus>,
+        Vec<f32>,
+        [usize; TestStatus::COUNT],
+    ) {
+        (
+            self.names,
+            self.name_ends,
+            self.statuses,
+            self.durations,
+            self.counts,
+        )
+    }
+
+    /// Whether every column has a value per test and every name lies within the names buffer
+    pub fn is_consistent(&self) -> bool {
+        let valid_ends = self.name_ends.is_sorted()
+            && self.name_ends.last().copied().unwrap_or_default() as usize == self.names.len()
+            && self
+                .name_ends
+                .iter()
+                .all(|end| self.names.is_char_boundary(*end as usize));
+        valid_ends
+            && self.name_ends.len() == self.statuses.len()
+            && self.durations.len() == self.statuses.len()
+            && self.counts.iter().sum::<usize>() == self.statuses.len()
+    }
+
+    /// Adds rows parsed after these, e.g. the next batch streamed by the results worker
+    pub fn append(&mut self, rows: TestResults) {
+        let offset = self.names.len() as u32;
+        self.names.push_str(&rows.names);
+        self.name_ends
+            .extend(rows.name_ends.iter().map(|end| end + offset));
+        self.statuses.extend(rows.statuses);
+        self.durations.extend(rows.durations);
+        for (count, rows) in self.counts.iter_mut().zip(rows.counts) {
+            *count += rows;
+        }
+    }
+
+    /// Copy of the rows in `range`, e.g. to hand a run over in batches
+    pub fn slice(&self, range: Range<usize>) -> TestResults {
+        let start = self.name_offset(range.start);
+        let statuses = self.statuses[range.clone()].to_vec();
+        let mut counts = [0; TestStatus::COUNT];
+        for status in &statuses {
+            counts[*status as usize] += 1;
+        }
+        TestResults {
+            names: self.names[start as usize..self.name_offset(range.end) as usize].to_string(),
+            name_ends: self.name_ends[range.clone()]
+                .iter()
+                .map(|end| end - start)
+                .collect(),
+            statuses,
+            durations: self.durations[range].to_vec(),
+            counts,
+        }
+    }
+
+    /// Start of the name of test `i` in the names buffer, its length past the last test
+    pub fn name_offset(&self, i: usize) -> u32 {
+        if i == 0 { 0 } else { self.name_ends[i - 1] }
+    }
+
+    fn push(&mut self, name: &str, status: TestStatus, seconds: Option<f32>) {
+        self.names.push_str(name);
+        self.name_ends.push(self.names.len() as u32);
+        self.statuses.push(status);
+        self.durations.push(seconds.unwrap_or(f32::NAN));
+        self.counts[status as usize] += 1;
+    }
+
+    pub fn len(&self) -> usize {
+        self.statuses.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.statuses.is_empty()
+    }
+
+    pub fn name(&self, i: usize) -> &str {
+        &self.names[self.name_offset(i) as usize..self.name_ends[i] as usize]
+    }
+
+    pub fn status(&self, i: usize) -> TestStatus {
+        self.statuses[i]
+    }
+
+    pub fn statuses(&self) -> &[TestStatus] {
+        &self.statuses
+    }
+
+    /// Number of tests per status over the whole run, counted while parsing
+    pub fn status_counts(&self) -> HashMap<TestStatus, usize> {
+        TestStatus::iter()
+            .map(|s| (s, self.counts[s as usize]))
+            .collect()
+    }
+
+    /// Duration in seconds, as written in the CSV.
+    /// `None` when it is missing, negative or too long for a [`Duration`].
+    pub fn seconds(&self, i: usize) -> Option<f32> {
+        self.duration(i).map(|_| self.durations[i])
+    }
+
+    pub fn duration(&self, i: usize) -> Option<Duration> {
+        Duration::try_from_secs_f32(self.durations[i]).ok()
+    }
+
+    pub fn get(&self, i: usize) -> TestResult<'_> {
+        TestResult {
+            name: self.name(i),
+            status: self.status(i),
+            duration: self.duration(i),
+        }
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = TestResult<'_>> + '_ {
+        (0..self.len()).map(|i| self.get(i))
+    }
+
+    pub fn position(&self, name: &str) -> Option<usize> {
+        (0..self.len()).find(|i| self.name(*i) == name)
+    }
+}
+
+/// Rows of results sorted by name, to look many tests up without scanning every row
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct NameIndex(Vec<u32>);
+
+impl NameIndex {
+    pub fn new(results: &TestResults) -> Self {
+        let mut rows = (0..results.len() as u32).collect::<Vec<_>>();
+        // Stable, so the first of several rows with the same name comes first
+        rows.sort_by(|a, b| results.name(*a as usize).cmp(results.name(*b as usize)));
+        Self(rows)
+    }
+
+    /// Same as [`TestResults::position`] for the results the index was built from
+    pub fn position(&self, results: &TestResults, name: &str) -> Option<usize> {
+        let i = self
+            .0
+            .partition_point(|row| results.name(*row as usize) < name);
+        let row = *self.0.get(i)? as usize;
+        (results.name(row) == name).then_some(row)
+    }
+}
+
+/// Parses every row once, rows without a valid status (e.g. the header) are left out
+pub fn parse_results(csv: &str) -> Result<TestResults, csv::Error> {
+    parse_rows(csv.as_bytes())
+}
+
+/// Parses a results CSV given chunk by chunk, e.g. as it is decompressed, with the same rules as
+/// [`parse_results`]. Rows split across chunks are parsed once they are complete.
+#[derive(Debug, Default)]
+pub struct ResultsParser {
+    // Start of the row the last chunk ended in
+    pending: Vec<u8>,
+    // Whether `pending` ends within a quoted field, which may hold newlines
+    in_quotes: bool,
+}
+
+impl ResultsParser {
+    /// Parses the rows completed by `chunk`
+    pub fn push(&mut self, chunk: &[u8]) -> Result<TestResults, csv::Error> {
+        let mut end = None;
+        for (i, byte) in chunk.iter().enumerate() {
+            match byte {
+                // An escaped quote toggles twice
+                b'"' => self.in_quotes = !self.in_quotes,
+                b'\n' if !self.in_quotes => end = Some(i + 1),
+                _ => {}
+            }
+        }
+        let Some(end) = end else {
+            self.pending.extend_from_slice(chunk);
+            return Ok(TestResults::default());
+        };
+
+        self.pending.extend_from_slice(&chunk[..end]);
+        let results = parse_rows(&self.pending);
+        self.pending.clear();
+        self.pending.extend_from_slice(&chunk[end..]);
+        results
+    }
+
+    /// Parses the last row, which may not end with a newline
+    pub fn finish(self) -> Result<TestResults, csv::Error> {
+        parse_rows(&self.pending)
+    }
+}
+
+fn parse_rows(csv: &[u8]) -> Result<TestResults, csv::Error> {
+    let mut reader = ReaderBuilder::new().has_headers(false).from_reader(csv);
+    let mut record = StringRecord::new();
+    let mut results = TestResults::default();
+    while reader.read_record(&mut record)? {
+        let (Some(name), Some(Ok(status))) =
+            (record.get(0), record.get(1).map(TestStatus::from_str))
+        else {
+            continue;
+        };
+        let seconds = record.get(2).and_then(|d| d.parse::<f32>().ok());
+        results.push(name, status, seconds);
+    }
+    Ok(results)
+}
+
+pub fn status_counts(statuses: impl IntoIterator<Item = TestStatus>) -> HashMap<TestStatus, usize> {
+    statuses.into_iter().fold(
+        HashMap::from_iter(TestStatus::iter().map(|s| (s, 0))),
+        |mut acc, status| {
+            *acc.entry(status).or_insert(0) += 1;
+            acc
+        },
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const CSV: &str = "testcase,status,duration
+dEQP-VK.api.smoke.triangle,Pass,0.5
+dEQP-VK.api.smoke.create_sampler,Fail,1.25
+dEQP-VK.wsi.display.get_props,Skip,
+dEQP-VK.api.info.bogus,NotAStatus,3
+";
+
+    #[test]
+    fn parse_skips_header_and_unknown_statuses() {
+        let results = parse_results(CSV).unwrap();
+        assert_eq!(results.len(), 3);
+        assert_eq!(results.name(1), "dEQP-VK.api.smoke.create_sampler");
+        assert_eq!(results.status(1), TestStatus::Fail);
+        assert_eq!(results.seconds(1), Some(1.25));
+        assert_eq!(results.seconds(2), None);
+        assert_eq!(results.position("dEQP-VK.wsi.display.get_props"), Some(2));
+        assert!(results.is_consistent());
+    }
+
+    #[test]
+    fn out_of_range_durations_are_invalid() {
+        let results = parse_results("a,Crash,1e30\nb,Pass,-1\nc,Pass,inf\nd,Pass,1e3\n").unwrap();
+        for i in 0..3 {
+            assert_eq!(results.seconds(i), None);
+            assert_eq!(results.duration(i), None);
+        }
+        assert_eq!(results.duration(3), Some(Duration::from_secs(1000)));
+    }
+
+    #[test]
+    fn counts_match_statuses() {
+        let results = parse_results(CSV).unwrap();
+        assert_eq!(
+            results.status_counts(),
+            status_counts(results.statuses().iter().copied())
+        );
+    }
+
+    #[test]
+    fn append_offsets_names() {
+        let mut results = parse_results(CSV).unwrap();
+        results.append(parse_results("dEQP-VK.api.smoke.other,Crash,2\n").unwrap());
+        assert_eq!(results.len(), 4);
+        assert_eq!(results.name(3), "dEQP-VK.api.smoke.other");
+        assert_eq!(results.status_counts()[&TestStatus::Crash], 1);
+        assert!(results.is_consistent());
+    }
+
+    #[test]
+    fn chunked_parse_matches_whole_parse() {
+        let csv =
+            format!("{CSV}\"dEQP-VK.quoted,\"\"name\"\"\nline\",Pass,2\ndEQP-VK.last,Crash,1");
+        let whole = parse_results(&csv).unwrap();
+        assert_eq!(whole.name(3), "dEQP-VK.quoted,\"name\"\nline");
+        for size in [1, 3, 7, 64] {
+            let mut parser = ResultsParser::default();
+            let mut results = TestResults::default();
+            for chunk in csv.as_bytes().chunks(size) {
+                results.append(parser.push(chunk).unwrap());
+            }
+            results.append(parser.finish().unwrap());
+            assert_eq!(results, whole, "chunks of {size} bytes");
+        }
+    }
+
+    #[test]
+    fn slices_are_standalone() {
+        let results = parse_results(CSV).unwrap();
+        let mut slices = results.slice(0..1);
+        slices.append(results.slice(1..3));
+        assert_eq!(slices, results);
+        let slice = results.slice(1..2);
+        assert_eq!(slice.name(0), "dEQP-VK.api.smoke.create_sampler");
+        assert!(slice.is_consistent());
+        assert!(results.slice(3..3).is_empty());
+    }
+
+    #[test]
+    fn name_index_finds_the_first_row() {
+        let results = parse_results("b,Pass,\na,Fail,\nc,Pass,\na,Crash,\n").unwrap();
+        let index = NameIndex::new(&results);
+        for name in ["a", "b", "c", "d", ""] {
+            assert_eq!(index.position(&results, name), results.position(name));
+        }
+    }
+
+    #[test]
+    fn from_columns_rejects_mismatched_columns() {
+        let counts = [0; TestStatus::COUNT];
+        assert!(
+            TestResults::from_columns("ab".to_string(), vec![1, 2], vec![], vec![], counts)
+                .is_none()
+        );
+    }
+
+    #[test]
+    fn pass_rate_leaves_out_skips() {
+        let counts = [
+            (TestStatus::Pass, 3),
+            (TestStatus::Fail, 1),
+            (TestStatus::Skip, 10),
+        ];
+        assert_eq!(pass_rate(counts), Some(75.0));
+        assert_eq!(pass_rate([(TestStatus::Skip, 2)]), None);
+    }
+
+    #[test]
+    fn hms_duration() {
+        let format = |ms| HMSDuration(Duration::from_millis(ms)).to_string();
+        assert_eq!(format(1_500), "0:0:1.500");
+        assert_eq!(format(61_005), "0:1:01.5");
+        assert_eq!(format(3_723_000), "1:02:03.0");
+    }
+}
@@ -0,0 +1,109 @@
+//! Compact binary form of parsed results, written ahead of time by the `preprocess` tool so the
+//! viewer loads a run without parsing any CSV: a header, then the runs as postcard compressed with zstd
+
+use crate::model::TestResults;
+use ruzstd::decoding::StreamingDecoder;
+use ruzstd::encoding::{CompressionLevel, compress_to_vec};
+use std::fmt;
+use std::io::Read;
+
+/// Starts every preprocessed file, followed by the format version
+pub const MAGIC: &[u8; 6] = b"VKCTS\0";
+pub const VERSION: u8 = 1;
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct FormatError(String);
+
+impl fmt::Display for FormatError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}", self.0)
+    }
+}
+
+impl std::error::Error for FormatError {}
+
+#[derive(Debug, Clone, PartialEq, serde::Serialize, serde::Deserialize)]
+pub struct Run {
+    pub name: String,
+    pub results: TestResults,
+}
+
+pub fn is_preprocessed(bytes: &[u8]) -> bool {
+    bytes.starts_with(MAGIC)
+}
+
+pub fn encode(runs: &[Run]) -> Result<Vec<u8>, FormatError> {
+    let payload = postcard::to_allocvec(runs)
+        .map_err(|e| FormatError(format!("Failed to serialize the results: {e}")))?;
+    let mut bytes = MAGIC.to_vec();
+    bytes.push(VERSION);
+    bytes.extend(compress_to_vec(
+        payload.as_slice(),
+        CompressionLevel::Fastest,
+    ));
+    Ok(bytes)
+}
+
+pub fn decode(bytes: &[u8]) -> Result<Vec<Run>, FormatError> {
+    let (version, compressed) = bytes
+        .strip_prefix(MAGIC.as_slice())
+        .and_then(|rest| rest.split_first())
+        .ok_or_else(|| FormatError("Not a preprocessed results file".to_string()))?;
+    if *version != VERSION {
+        return Err(FormatError(format!(
+            "Unsupported preprocessed format version {version}, expected {VERSION}"
+        )));
+    }
+
+    let mut payload = Vec::new();
+    StreamingDecoder::new(compressed)
+        .map_err(|e| FormatError(format!("Invalid zstd frame: {e}")))?
+        .read_to_end(&mut payload)
+        .map_err(|e| FormatError(format!("Failed to decompress the results: {e}")))?;
+    let runs = postcard::from_bytes::<Vec<Run>>(&payload)
+        .map_err(|e| FormatError(format!("Invalid preprocessed results: {e}")))?;
+    // Readers expect at least one run to show
+    if runs.is_empty() {
+        return Err(FormatError(
+            "The preprocessed results hold no run".to_string(),
+        ));
+    }
+    // Columns are trusted by the accessors of `TestResults`, a corrupted file must not make them panic
+    if let Some(run) = runs.iter().find(|run| !run.results.is_consistent()) {
+        return Err(FormatError(format!(
+            "Inconsistent results for {}",
+            run.name
+        )));
+    }
+    Ok(runs)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::model::parse_results;
+
+    #[test]
+    fn round_trip() {
+        let runs = vec![Run {
+            name: "results.csv".to_string(),
+            results: parse_results("a.b.c,Pass,1\na.b.d,Crash,\n").unwrap(),
+        }];
+        let bytes = encode(&runs).unwrap();
+        assert!(is_preprocessed(&bytes));
+        assert_eq!(decode(&bytes).unwrap(), runs);
+    }
+
+    #[test]
+    fn rejects_empty_files() {
+        assert!(decode(&encode(&[]).unwrap()).is_err());
+    }
+
+    #[test]
+    fn rejects_other_versions() {
+        let mut bytes = encode(&[]).unwrap();
+        bytes[MAGIC.len()] = VERSION + 1;
+        assert!(decode(&bytes).is_err());
+        assert!(decode(b"results.csv").is_err());
+    }
+}
@@ -0,0 +1,262 @@
+use crate::model::TestStatus;
+use regex::Regex;
+use std::fmt;
+use strum::IntoEnumIterator;
+
+/// Error of a search query, shown next to the search box
+#[derive(Debug, Clone, PartialEq)]
+pub struct QueryError(String);
+
+impl fmt::Display for QueryError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}", self.0)
+    }
+}
+
+impl std::error::Error for QueryError {}
+
+#[derive(Debug, Clone)]
+enum NameMatch {
+    Contains(String),
+    // Globs are compiled to anchored regexes
+    Regex(Regex),
+}
+
+impl NameMatch {
+    fn parse(pattern: &str) -> Result<Self, QueryError> {
+        if let Some(regex) = pattern.strip_prefix('~') {
+            return Regex::new(regex)
+                .map(Self::Regex)
+                .map_err(|e| QueryError(format!("Invalid regex `{regex}`: {e}")));
+        }
+        if !pattern.contains(['*', '?']) {
+            return Ok(Self::Contains(pattern.to_string()));
+        }
+        let mut regex = String::from("^");
+        for c in pattern.chars() {
+            match c {
+                '*' => regex.push_str(".*"),
+                '?' => regex.push('.'),
+                c => regex.push_str(&regex::escape(&c.to_string())),
+            }
+        }
+        regex.push('$');
+        Regex::new(&regex)
+            .map(Self::Regex)
+            .map_err(|e| QueryError(format!("Invalid pattern `{pattern}`: {e}")))
+    }
+
+    fn matches(&self, name: &str) -> bool {
+        match self {
+            Self::Contains(s) => name.contains(s.as_str()),
+            Self::Regex(regex) => regex.is_match(name),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum Comparison {
+    Less,
+    LessOrEqual,
+    Greater,
+    GreaterOrEqual,
+    Equal,
+}
+
+impl Comparison {
+    fn compare(&self, a: f32, b: f32) -> bool {
+        match self {
+            Self::Less => a < b,
+            Self::LessOrEqual => a <= b,
+            Self::Greater => a > b,
+            Self::GreaterOrEqual => a >= b,
+            Self::Equal => a == b,
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+enum Filter {
+    Name(NameMatch),
+    Status(Vec<TestStatus>),
+    // Duration in seconds
+    Duration(Comparison, f32),
+}
+
+impl Filter {
+    fn matches(&self, name: &str, status: TestStatus, duration: Option<f32>) -> bool {
+        match self {
+            Self::Name(pattern) => pattern.matches(name),
+            Self::Status(statuses) => statuses.contains(&status),
+            Self::Duration(comparison, value) => {
+                duration.is_some_and(|d| comparison.compare(d, *value))
+            }
+        }
+    }
+}
+
+fn parse_status(value: &str) -> Result<Vec<TestStatus>, QueryError> {
+    value
+        .split(',')
+        .filter(|s| !s.is_empty())
+        .map(|s| {
+            TestStatus::iter()
+                .find(|status| status.to_string().eq_ignore_ascii_case(s))
+                .ok_or_else(|| QueryError(format!("Unknown status `{s}`")))
+        })
+        .collect::<Result<Vec<_>, _>>()
+        .and_then(|statuses| {
+            if statuses.is_empty() {
+                Err(QueryError(
+                    "`status:` needs at least one status".to_string(),
+                ))
+            } else {
+                Ok(statuses)
+            }
+        })
+}
+
+// e.g. `>5s`, `<=250ms`, `2m`
+fn parse_duration(value: &str) -> Result<(Comparison, f32), QueryError> {
+    let (comparison, value) = [
+        (">=", Comparison::GreaterOrEqual),
+        ("<=", Comparison::LessOrEqual),
+        (">", Comparison::Greater),
+        ("<", Comparison::Less),
+        ("=", Comparison::Equal),
+    ]
+    .into_iter()
+    .find_map(|(op, comparison)| value.strip_prefix(op).map(|v| (comparison, v)))
+    .unwrap_or((Comparison::Equal, value));
+
+    let split = value
+        .find(|c: char| !c.is_ascii_digit() && c != '.')
+        .unwrap_or(value.len());
+    let (number, unit) = value.split_at(split);
+    let number = number
+        .parse::<f32>()
+        .map_err(|_| QueryError(format!("Invalid duration `{value}`")))?;
+    let scale = match unit {
+        "ms" => 0.001,
+        "" | "s" => 1.0,
+        "m" => 60.0,
+        "h" => 3600.0,
+        _ => {
+            return Err(QueryError(format!(
+                "Unknown duration unit `{unit}`, expected ms, s, m or h"
+            )));
+        }
+    };
+    Ok((comparison, number * scale))
+}
+
+/// A search query, every term must match:
+/// - `robustness` keeps the names containing the text, `dEQP-VK.api.*.smoke` is a glob on the whole name
+/// - `name:~regex` keeps the names matching the regex
+/// - `status:fail,crash` keeps the tests with one of the statuses
+/// - `duration:>5s` compares the duration with `<`, `<=`, `>`, `>=` or `=`, in ms, s, m or h
+/// - a leading `-` negates a term, e.g. `-name:~wsi`
+#[derive(Debug, Clone, Default)]
+pub struct Query {
+    // Filters and whether they are negated
+    filters: Vec<(Filter, bool)>,
+}
+
+impl Query {
+    pub fn parse(query: &str) -> Result<Self, QueryError> {
+        let mut filters = Vec::new();
+        for term in query.split_whitespace() {
+            let (term, negated) = match term.strip_prefix('-') {
+                Some(term) if !term.is_empty() => (term, true),
+                _ => (term, false),
+            };
+            let filter = match term.split_once(':') {
+                Some(("name", value)) => Filter::Name(NameMatch::parse(value)?),
+                Some(("status", value)) => Filter::Status(parse_status(value)?),
+                Some(("duration", value)) => {
+                    let (comparison, value) = parse_duration(value)?;
+                    Filter::Duration(comparison, value)
+                }
+                Some((key, _)) if !key.is_empty() && key.chars().all(char::is_alphabetic) => {
+                    return Err(QueryError(format!(
+                        "Unknown key `{key}:`, expected name:, status: or duration:"
+                    )));
+                }
+                // Test names never contain colons, but a regex might
+                _ => Filter::Name(NameMatch::parse(term)?),
+            };
+            filters.push((filter, negated));
+        }
+        Ok(Self { filters })
+    }
+
+    pub fn matches(&self, name: &str, status: TestStatus, duration: Option<f32>) -> bool {
+        self.filters
+            .iter()
+            .all(|(filter, negated)| filter.matches(name, status, duration) != *negated)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn matches(query: &str, name: &str, status: TestStatus, duration: Option<f32>) -> bool {
+        Query::parse(query).unwrap().matches(name, status, duration)
+    }
+
+    #[test]
+    fn names() {
+        let name = "dEQP-VK.api.smoke.triangle";
+        assert!(matches("smoke", name, TestStatus::Pass, None));
+        assert!(matches(
+            "dEQP-VK.api.*.triangle",
+            name,
+            TestStatus::Pass,
+            None
+        ));
+        assert!(!matches(
+            "dEQP-VK.api.*",
+            "dEQP-VK.wsi.x",
+            TestStatus::Pass,
+            None
+        ));
+        assert!(matches("name:~tri.ngle$", name, TestStatus::Pass, None));
+        assert!(!matches("-smoke", name, TestStatus::Pass, None));
+    }
+
+    #[test]
+    fn statuses_and_durations() {
+        assert!(matches("status:fail,crash", "a", TestStatus::Crash, None));
+        assert!(!matches("status:fail", "a", TestStatus::Pass, None));
+        assert!(matches(
+            "duration:>=250ms",
+            "a",
+            TestStatus::Pass,
+            Some(0.25)
+        ));
+        assert!(matches("duration:>1m", "a", TestStatus::Pass, Some(61.0)));
+        assert!(!matches("duration:>1m", "a", TestStatus::Pass, None));
+        assert!(matches(
+            "smoke status:pass",
+            "smoke",
+            TestStatus::Pass,
+            None
+        ));
+        assert!(!matches(
+            "smoke status:pass",
+            "smoke",
+            TestStatus::Fail,
+            None
+        ));
+    }
+
+    #[test]
+    fn errors() {
+        assert!(Query::parse("status:nope").is_err());
+        assert!(Query::parse("status:").is_err());
+        assert!(Query::parse("duration:>5y").is_err());
+        assert!(Query::parse("foo:bar").is_err());
+        assert!(Query::parse("name:~(").is_err());
+    }
+}
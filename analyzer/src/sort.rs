@@ -0,0 +1,163 @@
+use crate::model::{TestResults, TestStatus};
+use std::cmp::Ordering;
+use std::str::FromStr;
+use strum::EnumCount;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, strum::Display, strum::EnumString)]
+#[strum(serialize_all = "lowercase")]
+pub enum Column {
+    Name,
+    Duration,
+    Status,
+}
+
+impl Column {
+    // Durations and statuses are mostly sorted to find the slowest tests and the failures
+    pub const fn default_descending(&self) -> bool {
+        !matches!(self, Column::Name)
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct SortKey {
+    pub column: Column,
+    pub descending: bool,
+}
+
+impl SortKey {
+    pub const fn new(column: Column) -> Self {
+        Self {
+            column,
+            descending: column.default_descending(),
+        }
+    }
+}
+
+/// Sort keys as written in the URL, most significant first, e.g. `-duration,name`
+pub fn parse_sort(query: &str) -> Vec<SortKey> {
+    query
+        .split(',')
+        .filter_map(|key| {
+            let (key, descending) = match key.strip_prefix('-') {
+                Some(key) => (key, true),
+                None => (key, false),
+            };
+            Column::from_str(key)
+                .ok()
+                .map(|column| SortKey { column, descending })
+        })
+        .collect()
+}
+
+pub fn sort_query(keys: &[SortKey]) -> String {
+    keys.iter()
+        .map(|key| {
+            if key.descending {
+                format!("-{}", key.column)
+            } else {
+                key.column.to_string()
+            }
+        })
+        .collect::<Vec<_>>()
+        .join(",")
+}
+
+/// Sort keys after a click on a column header, `add` (shift-click) keeps the other keys.
+/// A column is sorted in its default direction, then the other one, then not at all.
+pub fn toggle_sort(keys: &[SortKey], column: Column, add: bool) -> Vec<SortKey> {
+    let mut keys = if add || (keys.len() == 1 && keys[0].column == column) {
+        keys.to_vec()
+    } else {
+        Vec::new()
+    };
+    match keys.iter().position(|key| key.column == column) {
+        Some(i) if keys[i].descending == column.default_descending() => {
+            keys[i].descending = !keys[i].descending
+        }
+        Some(i) => {
+            keys.remove(i);
+        }
+        None => keys.push(SortKey::new(column)),
+    }
+    keys
+}
+
+// Successes first, then failures, in declaration order
+fn status_rank(status: TestStatus) -> usize {
+    usize::from(status.is_failure()) * TestStatus::COUNT + status as usize
+}
+
+/// Stable sort of the given result indices, ties keep the CSV order
+pub fn sort_rows(results: &TestResults, rows: &mut [usize], keys: &[SortKey]) {
+    if keys.is_empty() {
+        return;
+    }
+
+    let seconds = |i: usize| results.seconds(i).unwrap_or(f32::NEG_INFINITY);
+
+    rows.sort_by(|&a, &b| {
+        keys.iter()
+            .map(|key| {
+                let ordering = match key.column {
+                    Column::Name => results.name(a).cmp(results.name(b)),
+                    Column::Duration => seconds(a).total_cmp(&seconds(b)),
+                    Column::Status => {
+                        status_rank(results.status(a)).cmp(&status_rank(results.status(b)))
+                    }
+                };
+                if key.descending {
+                    ordering.reverse()
+                } else {
+                    ordering
+                }
+            })
+            .find(|ordering| *ordering != Ordering::Equal)
+            .unwrap_or(Ordering::Equal)
+    });
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::model::parse_results;
+
+    #[test]
+    fn query_round_trip() {
+        let keys = parse_sort("-duration,name,bogus");
+        assert_eq!(
+            keys,
+            [
+                SortKey {
+                    column: Column::Duration,
+                    descending: true
+                },
+                SortKey {
+                    column: Column::Name,
+                    descending: false
+                },
+            ]
+        );
+        assert_eq!(sort_query(&keys), "-duration,name");
+    }
+
+    #[test]
+    fn toggle_cycles_directions() {
+        let keys = toggle_sort(&[], Column::Duration, false);
+        assert_eq!(keys, [SortKey::new(Column::Duration)]);
+        let keys = toggle_sort(&keys, Column::Duration, false);
+        assert!(!keys[0].descending);
+        assert!(toggle_sort(&keys, Column::Duration, false).is_empty());
+        let keys = toggle_sort(&[SortKey::new(Column::Status)], Column::Name, true);
+        assert_eq!(keys.len(), 2);
+    }
+
+    #[test]
+    fn rows() {
+        let results = parse_results("b,Pass,1\na,Fail,3\nc,Pass,\nd,Crash,2\n").unwrap();
+        let mut rows = (0..results.len()).collect::<Vec<_>>();
+        sort_rows(&results, &mut rows, &parse_sort("-duration"));
+        assert_eq!(rows, [1, 3, 0, 2]);
+        sort_rows(&results, &mut rows, &parse_sort("-status,name"));
+        assert_eq!(rows, [3, 1, 0, 2]);
+    }
+}
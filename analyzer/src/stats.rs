@@ -0,0 +1,202 @@
+//! Figures over a whole run, shared by the viewer and the command-line tools
+
+use crate::model::{TestResults, TestStatus};
+use std::collections::HashMap;
+
+/// Histogram bins cover 1ms to ~28h, four per decade
+pub const BINS_PER_DECADE: usize = 4;
+pub const MIN_DECADE: i32 = -3;
+pub const MAX_DECADE: i32 = 5;
+pub const BIN_COUNT: usize = (MAX_DECADE - MIN_DECADE) as usize * BINS_PER_DECADE;
+
+/// Indices of the `count` slowest tests with a valid duration, slowest first
+pub fn slowest(results: &TestResults, count: usize) -> Vec<usize> {
+    let seconds = |i: usize| results.seconds(i).unwrap_or_default();
+    let mut timed = (0..results.len())
+        .filter(|i| results.seconds(*i).is_some())
+        .collect::<Vec<_>>();
+    timed.sort_by(|a, b| seconds(*b).total_cmp(&seconds(*a)));
+    timed.truncate(count);
+    timed
+}
+
+/// Duration figures of a set of tests, in seconds
+#[derive(Debug, Clone, PartialEq, serde::Serialize)]
+pub struct DurationSummary {
+    pub count: usize,
+    pub p50: f32,
+    pub p90: f32,
+    pub p99: f32,
+    pub max: f32,
+    pub total: f32,
+}
+
+impl DurationSummary {
+    /// Figures of the tests with a valid duration and `status`, or of every test without one.
+    /// `None` when none of them has a duration.
+    pub fn new(results: &TestResults, status: Option<TestStatus>) -> Option<Self> {
+        let mut durations = (0..results.len())
+            .filter(|i| status.is_none_or(|s| results.status(*i) == s))
+            .filter_map(|i| results.seconds(i))
+            .collect::<Vec<_>>();
+        durations.sort_by(f32::total_cmp);
+        let max = *durations.last()?;
+        Some(Self {
+            count: durations.len(),
+            p50: percentile(&durations, 50.0),
+            p90: percentile(&durations, 90.0),
+            p99: percentile(&durations, 99.0),
+            max,
+            total: durations.iter().sum(),
+        })
+    }
+}
+
+/// Nearest-rank percentile of sorted values, `sorted` must not be empty
+pub fn percentile(sorted: &[f32], p: f32) -> f32 {
+    let rank = (p / 100.0 * sorted.len() as f32).ceil() as usize;
+    sorted[rank.clamp(1, sorted.len()) - 1]
+}
+
+/// Log-scale bin of a duration in seconds, shorter and longer ones go in the first and last bins
+pub fn duration_bin(seconds: f32) -> usize {
+    let position =
+        (seconds.max(f32::MIN_POSITIVE).log10() - MIN_DECADE as f32) * BINS_PER_DECADE as f32;
+    (position.max(0.0) as usize).min(BIN_COUNT - 1)
+}
+
+/// Number of tests per duration bin, see [`duration_bin`]
+pub fn duration_histogram(results: &TestResults) -> Vec<usize> {
+    (0..results.len()).filter_map(|i| results.seconds(i)).fold(
+        vec![0; BIN_COUNT],
+        |mut bins, seconds| {
+            bins[duration_bin(seconds)] += 1;
+            bins
+        },
+    )
+}
+
+/// Failures among the tests of a group, as counted by [`TestStatus::is_failure`](crate::model::TestStatus::is_failure)
+#[derive(Debug, Clone, PartialEq, serde::Serialize)]
+pub struct GroupFailures {
+    pub group: String,
+    pub failures: usize,
+    pub tests: usize,
+}
+
+/// Group of a test at `depth`, e.g. `dEQP-VK.api.smoke` for `dEQP-VK.api.smoke.triangle` at depth 3.
+/// Shallower tests are in their parent group, the test itself is never part of its group.
+pub fn group_of(name: &str, depth: usize) -> &str {
+    let parent = name.rfind('.').map_or("", |i| &name[..i]);
+    match parent.match_indices('.').nth(depth.saturating_sub(1)) {
+        Some((i, _)) => &parent[..i],
+        None => parent,
+    }
+}
+
+/// The `count` groups at `depth` with the most failures, most failures first
+pub fn failing_groups(results: &TestResults, depth: usize, count: usize) -> Vec<GroupFailures> {
+    let mut groups = HashMap::<&str, (usize, usize)>::new();
+    for test in results.iter() {
+        let (failures, tests) = groups.entry(group_of(test.name, depth)).or_default();
+        *tests += 1;
+        if test.status.is_failure() {
+            *failures += 1;
+        }
+    }
+
+    let mut groups = groups
+        .into_iter()
+        .filter(|(_, (failures, _))| *failures > 0)
+        .map(|(group, (failures, tests))| GroupFailures {
+            group: group.to_string(),
+            failures,
+            tests,
+        })
+        .collect::<Vec<_>>();
+    groups.sort_by(|a, b| {
+        b.failures
+            .cmp(&a.failures)
+            .then_with(|| a.group.cmp(&b.group))
+    });
+    groups.truncate(count);
+    groups
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::model::parse_results;
+
+    #[test]
+    fn groups() {
+        assert_eq!(
+            group_of("dEQP-VK.api.smoke.triangle", 3),
+            "dEQP-VK.api.smoke"
+        );
+        assert_eq!(group_of("dEQP-VK.api.smoke.triangle", 1), "dEQP-VK");
+        assert_eq!(group_of("dEQP-VK.api.triangle", 3), "dEQP-VK.api");
+        assert_eq!(group_of("triangle", 2), "");
+    }
+
+    #[test]
+    fn slowest_leave_out_missing_durations() {
+        let results = parse_results("a,Pass,1\nb,Pass,\nc,Fail,5\nd,Pass,2\n").unwrap();
+        assert_eq!(slowest(&results, 2), [2, 3]);
+        assert_eq!(slowest(&results, 10), [2, 3, 0]);
+    }
+
+    #[test]
+    fn nearest_rank_percentiles() {
+        let sorted = (1..=10).map(|i| i as f32).collect::<Vec<_>>();
+        assert_eq!(percentile(&sorted, 50.0), 5.0);
+        assert_eq!(percentile(&sorted, 90.0), 9.0);
+        assert_eq!(percentile(&sorted, 99.0), 10.0);
+        assert_eq!(percentile(&sorted, 0.0), 1.0);
+    }
+
+    #[test]
+    fn duration_summary_per_status() {
+        let results = parse_results("a,Pass,3\nb,Pass,\nc,Fail,5\nd,Pass,1\n").unwrap();
+        let summary = DurationSummary::new(&results, Some(TestStatus::Pass)).unwrap();
+        assert_eq!((summary.count, summary.p50, summary.max), (2, 1.0, 3.0));
+        assert_eq!(DurationSummary::new(&results, None).unwrap().total, 9.0);
+        assert_eq!(DurationSummary::new(&results, Some(TestStatus::Skip)), None);
+    }
+
+    #[test]
+    fn histogram_bins() {
+        assert_eq!(duration_bin(0.0), 0);
+        assert_eq!(duration_bin(0.001), 0);
+        assert_eq!(duration_bin(1.0), 3 * BINS_PER_DECADE);
+        assert_eq!(duration_bin(1e9), BIN_COUNT - 1);
+        let results = parse_results("a,Pass,1\nb,Pass,\nc,Fail,1.1\n").unwrap();
+        let bins = duration_histogram(&results);
+        assert_eq!(bins.iter().sum::<usize>(), 2);
+        assert_eq!(bins[duration_bin(1.0)], 2);
+    }
+
+    #[test]
+    fn failing_groups_by_failures() {
+        let results =
+            parse_results("x.a.1,Fail,\nx.a.2,Pass,\nx.b.1,Crash,\nx.b.2,Timeout,\nx.c.1,Skip,\n")
+                .unwrap();
+        let groups = failing_groups(&results, 2, 10);
+        assert_eq!(
+            groups,
+            [
+                GroupFailures {
+                    group: "x.b".to_string(),
+                    failures: 2,
+                    tests: 2
+                },
+                GroupFailures {
+                    group: "x.a".to_string(),
+                    failures: 1,
+                    tests: 2
+                },
+            ]
+        );
+        assert_eq!(failing_groups(&results, 2, 1).len(), 1);
+    }
+}
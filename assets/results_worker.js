@@ -0,0 +1,358 @@
+// Results worker: downloads archives, unzips them and parses their CSVs off the UI thread.
+// The unzipping and parsing are done by the `vulkan-cts-worker` crate compiled to WebAssembly (the
+// same code as the command-line tools), its wasm-bindgen output is passed in the query string.
+// Every request carries an `id` that is sent back with the reply, or with an `error`.
+// Long requests also send `progress` and `rows` messages with the same id before replying.
+// Archives and parsed runs are cached in IndexedDB, keyed by the SHA-256 of the archive: a new run
+// published at the same URL has another hash (and ETag) so it never hits the cache of the old one.
+
+// Progress messages are throttled to keep the UI thread free
+const PROGRESS_INTERVAL = 100;
+
+const DB_NAME = "vulkan-cts-viewer";
+const DB_VERSION = 2;
+// The least recently used archives and their runs are dropped past this
+const MAX_CACHED_ARCHIVES = 4;
+
+const params = new URL(self.location.href).searchParams;
+importScripts(params.get("bindings"));
+const ready = wasm_bindgen({ module_or_path: params.get("wasm") });
+
+const archives = new Map();
+let nextArchive = 0;
+// Requests the UI stopped waiting for
+const cancelled = new Set();
+
+self.onmessage = async (event) => {
+    const { id } = event.data;
+    try {
+        await ready;
+        const [reply, transfer] = await handle(event.data);
+        self.postMessage({ id, ...reply }, transfer);
+    } catch (e) {
+        self.postMessage({ id, error: String((e && e.message) || e) });
+    } finally {
+        cancelled.delete(id);
+    }
+};
+
+async function handle(request) {
+    switch (request.type) {
+        case "fetch":
+            return fetchArchive(request.id, request.name, request.url);
+        case "counts":
+            return countRuns(request.id, request.name, request.url);
+        case "open": {
+            const bytes = new Uint8Array(request.bytes);
+            const hash = await digest(bytes);
+            if (hash !== null) {
+                await touch(hash);
+            }
+            return open(request.name, bytes, hash);
+        }
+        case "parse":
+            return parse(request.id, request.archive, request.run, request.statuses);
+        case "close":
+            archives.get(request.archive)?.archive.free();
+            archives.delete(request.archive);
+            return [{}, []];
+        case "cancel":
+            cancelled.add(request.request);
+            return [{}, []];
+        default:
+            throw new Error(`Unknown request ${request.type}`);
+    }
+}
+
+function checkCancelled(id) {
+    if (cancelled.has(id)) {
+        throw new Error("Cancelled");
+    }
+}
+
+let database = null;
+
+// Resolves to null when IndexedDB is unavailable, e.g. in some private windows
+function openDatabase() {
+    if (database === null) {
+        database = new Promise((resolve, reject) => {
+            const request = indexedDB.open(DB_NAME, DB_VERSION);
+            request.onupgradeneeded = () => {
+                // The stores of older versions are kept
+                const create = (store) =>
+                    request.result.objectStoreNames.contains(store) || request.result.createObjectStore(store);
+                // Archive bytes and last use, by hash
+                create("archives");
+                create("used");
+                // Archive hash, by `<url> <ETag>`
+                create("etags");
+                // Batches of parsed rows, by `<hash>/<run>`
+                create("runs");
+                // Status counts of every run, by `<url> <ETag>` and by hash
+                create("counts");
+            };
+            request.onsuccess = () => resolve(request.result);
+            request.onerror = () => reject(request.error);
+        }).catch((e) => {
+            console.warn(`Results cache unavailable: ${e}`);
+            return null;
+        });
+    }
+    return database;
+}
+
+function completed(transaction) {
+    return new Promise((resolve, reject) => {
+        transaction.oncomplete = () => resolve();
+        transaction.onerror = () => reject(transaction.error);
+        transaction.onabort = () => reject(transaction.error);
+    });
+}
+
+function requested(request) {
+    return new Promise((resolve, reject) => {
+        request.onsuccess = () => resolve(request.result);
+        request.onerror = () => reject(request.error);
+    });
+}
+
+// The cache is an optimisation, failing to use it must not fail the request
+async function cached(store, key) {
+    try {
+        const db = await openDatabase();
+        return db === null ? undefined : await requested(db.transaction(store).objectStore(store).get(key));
+    } catch (e) {
+        console.warn(`Failed to read the results cache: ${e}`);
+        return undefined;
+    }
+}
+
+async function cache(store, key, value) {
+    try {
+        const db = await openDatabase();
+        if (db !== null) {
+            const transaction = db.transaction(store, "readwrite");
+            transaction.objectStore(store).put(value, key);
+            await completed(transaction);
+        }
+    } catch (e) {
+        console.warn(`Failed to write the results cache: ${e}`);
+    }
+}
+
+// Marks an archive as used and drops the least recently used ones
+async function touch(hash) {
+    try {
+        const db = await openDatabase();
+        if (db === null) {
+            return;
+        }
+        const transaction = db.transaction(["archives", "used", "runs"], "readwrite");
+        const used = transaction.objectStore("used");
+        used.put(Date.now(), hash);
+        const [hashes, times] = await Promise.all([requested(used.getAllKeys()), requested(used.getAll())]);
+        const stale = hashes
+            .map((key, i) => [key, times[i]])
+            .sort((a, b) => b[1] - a[1])
+            .slice(MAX_CACHED_ARCHIVES);
+        for (const [key] of stale) {
+            used.delete(key);
+            transaction.objectStore("archives").delete(key);
+            transaction.objectStore("runs").delete(IDBKeyRange.bound(`${key}/`, `${key}/\uffff`));
+        }
+        await completed(transaction);
+    } catch (e) {
+        console.warn(`Failed to write the results cache: ${e}`);
+    }
+}
+
+// Null when the hash cannot be computed, `crypto.subtle` is only there in secure contexts
+async function digest(bytes) {
+    if (!self.crypto || !self.crypto.subtle) {
+        return null;
+    }
+    const hash = new Uint8Array(await self.crypto.subtle.digest("SHA-256", bytes));
+    return Array.from(hash, (byte) => byte.toString(16).padStart(2, "0")).join("");
+}
+
+class Progress {
+    constructor(id, phase, total) {
+        this.id = id;
+        this.phase = phase;
+        this.total = total;
+        this.start = performance.now();
+        this.last = 0;
+    }
+
+    // The time left is estimated from the average rate so far, false when the report is throttled
+    report(bytes, rows) {
+        const now = performance.now();
+        if (now - this.last < PROGRESS_INTERVAL) {
+            return false;
+        }
+        this.last = now;
+        const total = this.total !== null && bytes <= this.total ? this.total : null;
+        const eta = total !== null && bytes > 0 ? (((now - this.start) / 1000) * (total - bytes)) / bytes : null;
+        self.postMessage({ id: this.id, progress: { phase: this.phase, bytes, total, rows, eta } });
+        return true;
+    }
+}
+
+async function fetchArchive(id, name, url) {
+    const response = await fetch(url);
+    if (!response.ok) {
+        throw new Error(`Failed to fetch ${url}: HTTP ${response.status}`);
+    }
+
+    // An archive already seen with this ETag is not downloaded again.
+    // Cross-origin servers have to expose the header, otherwise the hash of the download is used.
+    const etag = response.headers.get("ETag");
+    if (etag !== null) {
+        const hash = await cached("etags", `${url} ${etag}`);
+        const bytes = hash === undefined ? undefined : await cached("archives", hash);
+        if (bytes !== undefined) {
+            response.body.cancel().catch(() => {});
+            await touch(hash);
+            return open(name, bytes, hash);
+        }
+    }
+
+    const bytes = await download(id, response);
+    const hash = await digest(bytes);
+    if (hash !== null) {
+        await cache("archives", hash, bytes);
+        if (etag !== null) {
+            await cache("etags", `${url} ${etag}`, hash);
+        }
+        await touch(hash);
+    }
+    return open(name, bytes, hash);
+}
+
+// Counts the tests per status of every run of an archive, for the trends page.
+// Only the counts are cached, the archives would evict the ones the user opened: an index usually
+// lists more of them than the cache holds.
+async function countRuns(id, name, url) {
+    const response = await fetch(url);
+    if (!response.ok) {
+        throw new Error(`Failed to fetch ${url}: HTTP ${response.status}`);
+    }
+
+    const etag = response.headers.get("ETag");
+    const tag = etag === null ? null : `${url} ${etag}`;
+    const known = tag === null ? undefined : await cached("counts", tag);
+    if (known !== undefined) {
+        response.body.cancel().catch(() => {});
+        return [{ runs: known }, []];
+    }
+
+    const bytes = await download(id, response);
+    const hash = await digest(bytes);
+    let runs = hash === null ? undefined : await cached("counts", hash);
+    if (runs === undefined) {
+        const archive = await wasm_bindgen.ResultsArchive.open(name, bytes);
+        try {
+            runs = [];
+            for (const [i, run] of archive.runs.entries()) {
+                runs.push({ name: run, counts: await archive.counts(i) });
+                checkCancelled(id);
+            }
+        } finally {
+            archive.free();
+        }
+        if (hash !== null) {
+            await cache("counts", hash, runs);
+        }
+    }
+    if (tag !== null) {
+        await cache("counts", tag, runs);
+    }
+    return [{ runs }, []];
+}
+
+async function download(id, response) {
+    // Unknown when the server streams or compresses the response
+    const length = Number(response.headers.get("Content-Length"));
+    const progress = new Progress(id, "download", length > 0 ? length : null);
+    const reader = response.body.getReader();
+    const chunks = [];
+    let bytes = 0;
+    for (;;) {
+        const { done, value } = await reader.read();
+        checkCancelled(id);
+        if (done) {
+            break;
+        }
+        chunks.push(value);
+        bytes += value.length;
+        progress.report(bytes, 0);
+    }
+
+    const archive = new Uint8Array(bytes);
+    let offset = 0;
+    for (const chunk of chunks) {
+        archive.set(chunk, offset);
+        offset += chunk.length;
+    }
+    return archive;
+}
+
+// Lists the runs of a results file, nothing is decompressed until a run is parsed.
+// Preprocessed files are decoded here, their runs are then sent in batches like parsed ones.
+async function open(name, bytes, hash) {
+    const archive = await wasm_bindgen.ResultsArchive.open(name, bytes);
+    const handle = nextArchive++;
+    archives.set(handle, { archive, hash });
+    return [{ archive: handle, runs: archive.runs }, []];
+}
+
+// Rows in the same columns as `TestResults`, see `columns` in the worker crate
+function transferables(rows) {
+    return [rows.names.buffer, rows.nameEnds.buffer, rows.statuses.buffer, rows.durations.buffer];
+}
+
+async function parse(id, handle, run, statuses) {
+    const archive = archives.get(handle);
+    if (archive === undefined) {
+        throw new Error(`Unknown archive ${handle}`);
+    }
+
+    // Parsed before, as long as the statuses are still the same
+    const key = `${archive.hash}/${run}`;
+    const hit = archive.hash === null ? undefined : await cached("runs", key);
+    if (hit !== undefined && hit.statuses.join() === statuses.join()) {
+        for (const rows of hit.batches) {
+            self.postMessage({ id, rows }, transferables(rows));
+        }
+        return [{}, []];
+    }
+
+    const progress = new Progress(id, "parse", null);
+    // Copies of the batches sent, the sent ones are moved to the UI thread
+    const batches = [];
+    let sent = 0;
+    await archive.archive.parse(run, async (rows, bytes, total) => {
+        if (rows !== undefined) {
+            batches.push({
+                names: rows.names.slice(),
+                nameEnds: rows.nameEnds.slice(),
+                statuses: rows.statuses.slice(),
+                durations: rows.durations.slice(),
+                counts: rows.counts.slice(),
+            });
+            self.postMessage({ id, rows }, transferables(rows));
+            sent += rows.statuses.length;
+        }
+        progress.total = total;
+        // Gives the worker a chance to get a cancellation between chunks
+        if (progress.report(bytes, sent)) {
+            await new Promise((resolve) => setTimeout(resolve));
+        }
+        checkCancelled(id);
+    });
+
+    if (archive.hash !== null) {
+        await cache("runs", key, { statuses, batches });
+    }
+    return [{}, []];
+}
@@ -0,0 +1,14 @@
+#!/bin/sh
+# Builds the results worker into assets/worker, which the web app bundles: run it before `dx build`
+# or `dx serve`. The wasm-bindgen CLI has to match the crate, pinned in worker/Cargo.toml.
+set -e
+cd "$(dirname "$0")"
+
+version=$(sed -n 's/^wasm-bindgen = "=\(.*\)"/\1/p' worker/Cargo.toml)
+if [ "$(wasm-bindgen --version 2>/dev/null)" != "wasm-bindgen $version" ]; then
+    cargo install wasm-bindgen-cli --version "$version" --locked
+fi
+
+cargo build -p vulkan-cts-worker --release --target wasm32-unknown-unknown
+wasm-bindgen --target no-modules --no-typescript --out-dir assets/worker \
+    target/wasm32-unknown-unknown/release/vulkan_cts_worker.wasm
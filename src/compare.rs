@@ -0,0 +1,323 @@
+use crate::components::select::*;
+use crate::landing::{PAGE_SIZE, Pagination, ResultsDropZone, StatCard, StatusBadge, percentage};
+use crate::loader::Loader;
+use crate::results::{Archive, TestResults, TestStatus, use_archive, use_runs};
+use crate::routes::Route;
+use dioxus::prelude::*;
+use dioxus_primitives::toast::{ToastOptions, use_toast};
+use std::collections::HashMap;
+use strum::{EnumCount, IntoEnumIterator};
+use vulkan_cts_analyzer::comparison::{Change, compare};
+
+#[derive(Clone, PartialEq)]
+struct LoadedRun {
+    name: String,
+    results: TestResults,
+}
+
+#[component]
+pub fn Compare(results: String) -> Element {
+    use_archive(results).load("Loading CTS results")?;
+
+    let runs = use_runs();
+    let base = use_signal(|| None::<LoadedRun>);
+    let head = use_signal(|| None::<LoadedRun>);
+
+    // Compare the active run against the one before it by default
+    let (default_base, default_head) = {
+        let runs = runs.read();
+        let active = runs.active;
+        (active.checked_sub(1), Some(active))
+    };
+
+    let comparisons = use_memo(move || match (&*base.read(), &*head.read()) {
+        (Some(base), Some(head)) => compare(&base.results, &head.results),
+        _ => Vec::new(),
+    });
+
+    let counts = use_memo(move || {
+        comparisons.read().iter().fold(
+            HashMap::from_iter(Change::iter().map(|c| (c, 0_usize))),
+            |mut acc, comparison| {
+                *acc.entry(comparison.change).or_insert(0) += 1;
+                acc
+            },
+        )
+    });
+
+    let total = use_memo(move || comparisons.read().len() as f32);
+
+    let stats_cards = Change::iter().map(|c| {
+        rsx! {
+            StatCard {
+                name: c.to_string(),
+                color: c.color().to_string(),
+                count: counts.read()[&c],
+                stat: percentage(counts.read()[&c], total()),
+            }
+        }
+    });
+
+    let mut search: Signal<Option<String>> = use_signal(|| None);
+    let mut filter: Signal<Option<Change>> = use_signal(|| None);
+    let current_page = use_memo(move || {
+        let _ = filter();
+        let _ = search();
+        0_usize
+    });
+
+    // Without a filter every test but the unchanged ones is listed
+    let changed = use_memo(move || {
+        let f = filter();
+        let search = search();
+        comparisons
+            .read()
+            .iter()
+            .filter(|c| match f {
+                Some(wanted) => c.change == wanted,
+                None => c.change != Change::Unchanged,
+            })
+            .filter(|c| search.as_ref().is_none_or(|s| c.name.contains(s)))
+            .cloned()
+            .collect::<Vec<_>>()
+    });
+    let page_count = use_memo(move || changed.read().len().saturating_sub(1) / PAGE_SIZE);
+    let page = use_memo(move || {
+        changed
+            .read()
+            .iter()
+            .skip(current_page() * PAGE_SIZE)
+            .take(PAGE_SIZE)
+            .cloned()
+            .collect::<Vec<_>>()
+    });
+
+    let changes = Change::iter().enumerate().map(|(i, c)| {
+        rsx! {
+            SelectOption::<Option<Change>> { index: i, value: c, text_value: "{c}",
+                {format!("{} {c}", c.emoji())}
+                SelectItemIndicator {}
+            }
+        }
+    });
+
+    rsx! {
+        div {
+            class: "flex flex-col space-y-4 rounded-3xl p-4 pt-8 w-full h-fit shadow-xl shadow-slate-950",
+            style: "background: linear-gradient(145deg, #020617 0, #02081f 60%, #020617 100%);",
+            div { class: "grid grid-cols-1 md:grid-cols-2 gap-4",
+                RunPicker { label: "Base", run: base, default_run: default_base }
+                RunPicker { label: "Head", run: head, default_run: default_head }
+            }
+            p { class: "text-xs text-slate-400",
+                "A regression is a test going from a success to a failure, a fix is the opposite. Status changes that stay on the same side (e.g. Fail to Crash) are counted as unchanged."
+            }
+            div { class: "grid grid-cols-1 sm:grid-cols-2 md:grid-cols-3 lg:grid-cols-5 gap-4",
+                {stats_cards}
+            }
+            div { class: "mt-12 w-full flex flex-col md:flex-row justify-between text-gray-400 text-sm gap-4 items-center",
+                input {
+                    class: "w-full border-1 border-gray-700 px-3 py-1 rounded-lg text-sm",
+                    style: "background: radial-gradient(circle at top, rgba(56, 189, 248, 0.1), rgba(15, 23, 42, 1));",
+                    r#type: "search",
+                    placeholder: "Search tests...",
+                    oninput: move |event: FormEvent| {
+                        search.set(if event.value().is_empty() { None } else { Some(event.value()) })
+                    },
+                }
+                p { class: "my-auto w-fit text-nowrap",
+                    "{changed.read().len()} tests, page {current_page() + 1} of {page_count() + 1}"
+                }
+                div { class: "hidden lg:block",
+                    Pagination { current_page, page_count, small: false }
+                }
+                div { class: "block lg:hidden",
+                    Pagination { current_page, page_count, small: true }
+                }
+            }
+            div { class: "w-full bg-gray-900 overflow-auto border-1 border-slate-700 rounded-lg text-gray-400",
+                table { class: "w-full border-collapse border-spacing-0",
+                    tr {
+                        class: "border-b-1 border-slate-700",
+                        style: "background: radial-gradient(circle at top, rgba(56, 189, 248, 0.1), rgba(15, 23, 42, 1));",
+                        th { class: "text-left uppercase bold whitespace-nowrap py-2 px-3",
+                            "Test name"
+                        }
+                        th { class: "uppercase bold whitespace-nowrap py-2 px-3", "Base" }
+                        th { class: "uppercase bold whitespace-nowrap py-2 px-3", "Head" }
+                        th { class: "uppercase bold whitespace-nowrap py-2 px-3",
+                            Select::<Option<Change>> {
+                                on_value_change: move |value: Option<Option<Change>>| filter.set(value.unwrap_or(None)),
+                                SelectTrigger {
+                                    class: "select-trigger mx-auto w-fit !bg-transparent !shadow-none !text-gray-400 cursor-pointer uppercase",
+                                    aria_label: "Select Trigger",
+                                    SelectValue {
+                                        class: "!bg-transparent !shadow-none !text-gray-400",
+                                        placeholder: "CHANGE",
+                                    }
+                                }
+                                SelectList { aria_label: "Select change",
+                                    SelectGroup {
+                                        {changes}
+                                        SelectOption::<Option<Change>> {
+                                            index: Change::COUNT,
+                                            value: None,
+                                            text_value: "Change",
+                                            "🔄 All changes"
+                                            SelectItemIndicator {}
+                                        }
+                                    }
+                                }
+                            }
+                        }
+                    }
+                    for comparison in page.iter() {
+                        tr { class: "text-sm hover:bg-[#38bef7]/5",
+                            td { class: "py-2 px-3",
+                                Link {
+                                    class: "hover:underline",
+                                    to: Route::test(&comparison.name, runs.read().results_query()),
+                                    "{comparison.name}"
+                                }
+                            }
+                            td { class: "py-2 px-3",
+                                OptionalStatusBadge { status: comparison.base }
+                            }
+                            td { class: "py-2 px-3",
+                                OptionalStatusBadge { status: comparison.head }
+                            }
+                            td { class: "py-2 px-3",
+                                ChangeBadge { change: comparison.change }
+                            }
+                        }
+                    }
+                }
+            }
+        }
+    }
+}
+
+#[component]
+fn RunPicker(
+    label: String,
+    mut run: Signal<Option<LoadedRun>>,
+    default_run: Option<usize>,
+) -> Element {
+    let toast = use_toast();
+    let runs = use_runs();
+
+    let load = move |archive: Archive, index: usize| {
+        let Some(name) = archive.runs.get(index).cloned() else {
+            return;
+        };
+        spawn(async move {
+            match archive.results(index).await {
+                Ok(results) => run.set(Some(LoadedRun { name, results })),
+                Err(e) => {
+                    error!("Failed to load {name}: {e}");
+                    toast.error(
+                        "Error".to_string(),
+                        ToastOptions::new().description(format!("Failed to load {name}")),
+                    );
+                }
+            }
+        });
+    };
+
+    use_hook(move || {
+        if let (Some(archive), Some(index)) = (runs.peek().archive.clone(), default_run) {
+            load(archive, index);
+        }
+    });
+
+    let onfile = move |(name, bytes): (String, Vec<u8>)| async move {
+        match Archive::open(name.clone(), bytes).await {
+            Ok(archive) => load(archive, 0),
+            Err(e) => {
+                error!("Failed to read {name}: {e}");
+                toast.error(
+                    "Error".to_string(),
+                    ToastOptions::new().description(format!("Failed to read {name}")),
+                );
+            }
+        }
+    };
+
+    let summary = match &*run.read() {
+        Some(run) => format!("{} ({} tests)", run.name, run.results.len()),
+        None => "No run selected".to_string(),
+    };
+    let archive = runs.read().archive.clone();
+    let options = archive.iter().flat_map(|archive| {
+        archive.runs.iter().enumerate().map(|(i, name)| {
+            rsx! {
+                SelectOption::<usize> { index: i, value: i, text_value: "{name}",
+                    "{name}"
+                    SelectItemIndicator {}
+                }
+            }
+        })
+    });
+
+    rsx! {
+        div { class: "rounded-2xl p-4 border-1 border-slate-800 shadow-xl shadow-[#02081f] w-full h-fit bg-[#090f21] flex flex-col space-y-2",
+            div { class: "flex flex-row justify-between items-center",
+                h3 { class: "text-sm text-gray-300", "{label}" }
+                p { class: "text-xs text-slate-400", "{summary}" }
+            }
+            if let Some(archive) = archive.clone() {
+                Select::<usize> {
+                    on_value_change: move |value: Option<usize>| {
+                        if let Some(index) = value {
+                            load(archive.clone(), index);
+                        }
+                    },
+                    SelectTrigger {
+                        class: "select-trigger w-full !bg-transparent !shadow-none !text-gray-400 cursor-pointer",
+                        aria_label: "Select run",
+                        SelectValue {
+                            class: "!bg-transparent !shadow-none !text-gray-400",
+                            placeholder: "Pick a run from the loaded archive",
+                        }
+                    }
+                    SelectList { aria_label: "Select run",
+                        SelectGroup { {options} }
+                    }
+                }
+            }
+            ResultsDropZone { on_file: onfile }
+        }
+    }
+}
+
+#[component]
+fn OptionalStatusBadge(status: Option<TestStatus>) -> Element {
+    match status {
+        Some(status) => rsx! {
+            StatusBadge { status }
+        },
+        None => rsx! {
+            p { class: "mx-auto w-fit text-xs text-slate-500", "—" }
+        },
+    }
+}
+
+#[component]
+fn ChangeBadge(change: Change) -> Element {
+    rsx! {
+        div {
+            class: "mx-auto border-1 py-1 px-3 rounded-3xl text-xs w-fit select-none text-nowrap",
+            style: format!(
+                r#"
+                    background-color: {}0F;
+                    color: {};
+                    border-color: {};
+                "#,
+                change.color(),
+                change.color(),
+                change.color(),
+            ),
+            "{change.emoji()} {change}"
+        }
+    }
+}
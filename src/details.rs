@@ -0,0 +1,213 @@
+use crate::group::Breadcrumbs;
+use crate::landing::StatusBadge;
+use crate::loader::Loader;
+use crate::results::{
+    HMSDuration, IndexedRuns, NameIndex, TestResults, TestStatus, use_active_run, use_archive,
+    use_indexed_runs, use_runs,
+};
+use dioxus::CapturedError;
+use dioxus::prelude::*;
+use dioxus_primitives::toast::{ToastOptions, use_toast};
+use std::collections::HashMap;
+use std::rc::Rc;
+use std::time::Duration;
+
+#[derive(Clone, PartialEq)]
+struct RunResult {
+    run: String,
+    status: Option<TestStatus>,
+    duration: Option<Duration>,
+}
+
+fn find(results: &TestResults, name: &str) -> (Option<TestStatus>, Option<Duration>) {
+    status_at(results, results.position(name))
+}
+
+fn status_at(results: &TestResults, row: Option<usize>) -> (Option<TestStatus>, Option<Duration>) {
+    row.map(|i| (Some(results.status(i)), results.duration(i)))
+        .unwrap_or((None, None))
+}
+
+fn deqp_command(name: &str) -> String {
+    format!("./deqp-vk --deqp-case={name}")
+}
+
+#[component]
+pub fn Test(name: String, results: String) -> Element {
+    use_archive(results).load("Loading CTS results")?;
+
+    let toast = use_toast();
+    let runs = use_runs();
+    let active_run = use_active_run();
+    let mut indexed = use_indexed_runs();
+
+    let (status, duration) = find(&active_run.read().results, &name);
+    let active = runs.read().active;
+
+    // Only a new archive changes the other runs, not switching runs or rows streaming in
+    let archive = use_memo(move || runs.read().archive.clone());
+
+    // Every other run of the archive is decompressed to look the test up, once per archive
+    let history = use_resource(use_reactive!(|name| async move {
+        let Some(archive) = archive() else {
+            return Ok(Vec::new());
+        };
+        if indexed.peek().archive.as_ref() != Some(&archive) {
+            indexed.set(IndexedRuns {
+                archive: Some(archive.clone()),
+                runs: HashMap::new(),
+            });
+        }
+        let active = runs.peek().active;
+        let mut out = Vec::with_capacity(archive.runs.len());
+        for (i, run) in archive.runs.iter().enumerate() {
+            let (status, duration) = if i == active {
+                find(&active_run.peek().results, &name)
+            } else {
+                let cached = indexed.peek().runs.get(&i).cloned();
+                let run = match cached {
+                    Some(run) => run,
+                    None => {
+                        let results = archive.results(i).await?;
+                        let index = NameIndex::new(&results);
+                        let run = Rc::new((results, index));
+                        indexed.write().runs.insert(i, run.clone());
+                        run
+                    }
+                };
+                let (results, index) = &*run;
+                status_at(results, index.position(results, &name))
+            };
+            out.push(RunResult {
+                run: run.clone(),
+                status,
+                duration,
+            });
+        }
+        Ok::<_, CapturedError>(out)
+    }));
+
+    // The active run is shown as loaded so far, the others as they were looked up
+    let row = move |(i, result): (usize, &RunResult)| {
+        if i == active {
+            (
+                i,
+                RunResult {
+                    status,
+                    duration,
+                    ..result.clone()
+                },
+            )
+        } else {
+            (i, result.clone())
+        }
+    };
+
+    let command = deqp_command(&name);
+    let segments = name.split('.').map(str::to_string).collect::<Vec<_>>();
+
+    let oncopy = {
+        let command = command.clone();
+        move |_| {
+            let eval = document::eval("await navigator.clipboard.writeText(await dioxus.recv());");
+            match eval.send(command.clone()) {
+                Ok(()) => toast.success(
+                    "Copied".to_string(),
+                    ToastOptions::new().description("Command line copied to the clipboard"),
+                ),
+                Err(e) => error!("Failed to copy the command line: {e}"),
+            }
+        }
+    };
+
+    rsx! {
+        div {
+            class: "flex flex-col space-y-6 rounded-3xl p-4 pt-8 w-full h-fit shadow-xl shadow-slate-950 text-gray-400",
+            style: "background: linear-gradient(145deg, #020617 0, #02081f 60%, #020617 100%);",
+            Breadcrumbs { segments }
+            div { class: "flex flex-row flex-wrap items-center gap-6",
+                if let Some(status) = status {
+                    div { class: "flex flex-col space-y-1",
+                        p { class: "text-xs uppercase", "Status" }
+                        StatusBadge { status }
+                    }
+                } else {
+                    p { class: "text-sm", "This test is not part of the active run" }
+                }
+                if let Some(duration) = duration {
+                    div { class: "flex flex-col space-y-1",
+                        p { class: "text-xs uppercase", "Duration (H:M:S.MS)" }
+                        p { class: "text-white",
+                            "{HMSDuration(duration)}"
+                        }
+                    }
+                }
+            }
+            div { class: "flex flex-col space-y-1",
+                p { class: "text-xs uppercase", "Reproduce" }
+                div { class: "flex flex-row items-center gap-2",
+                    code { class: "grow bg-gray-900 border-1 border-slate-700 rounded-lg px-3 py-2 text-sm text-gray-300 overflow-x-auto whitespace-nowrap",
+                        "{command}"
+                    }
+                    button { class: "pagination-button", onclick: oncopy, "Copy" }
+                }
+            }
+            div { class: "flex flex-col space-y-1",
+                p { class: "text-xs uppercase", "Other runs" }
+                {match &*history.read() {
+                    None => rsx! {
+                        p { class: "text-sm", "Looking the test up in the other runs..." }
+                    },
+                    Some(Err(e)) => rsx! {
+                        p { class: "text-sm", "Failed to read the other runs: {e}" }
+                    },
+                    Some(Ok(history)) => rsx! {
+                        div { class: "w-full bg-gray-900 overflow-auto border-1 border-slate-700 rounded-lg",
+                            table { class: "w-full border-collapse border-spacing-0",
+                                tr {
+                                    class: "border-b-1 border-slate-700",
+                                    style: "background: radial-gradient(circle at top, rgba(56, 189, 248, 0.1), rgba(15, 23, 42, 1));",
+                                    th { class: "text-left uppercase bold whitespace-nowrap py-2 px-3",
+                                        "Run"
+                                    }
+                                    th { class: "text-left uppercase bold whitespace-nowrap py-2 px-3",
+                                        "Duration (H:M:S.MS)"
+                                    }
+                                    th { class: "uppercase bold whitespace-nowrap py-2 px-3",
+                                        "Status"
+                                    }
+                                }
+                                for (i , result) in history.iter().enumerate().map(row) {
+                                    tr { class: "text-sm hover:bg-[#38bef7]/5",
+                                        td { class: "py-2 px-3",
+                                            "{result.run}"
+                                            if i == active {
+                                                span { class: "ml-2 text-xs text-slate-500", "(active)" }
+                                            }
+                                        }
+                                        td { class: "py-2 px-3",
+                                            if let Some(duration) = result.duration {
+                                                "{HMSDuration(duration)}"
+                                            } else {
+                                                "—"
+                                            }
+                                        }
+                                        td { class: "py-2 px-3",
+                                            if let Some(status) = result.status {
+                                                StatusBadge { status }
+                                            } else {
+                                                p { class: "mx-auto w-fit text-xs text-slate-500",
+                                                    "Not run"
+                                                }
+                                            }
+                                        }
+                                    }
+                                }
+                            }
+                        }
+                    },
+                }}
+            }
+        }
+    }
+}
@@ -0,0 +1,220 @@
+use crate::landing::{StatCard, StatusBadge};
+use crate::loader::Loader;
+use crate::results::{HMSDuration, TestStatus, use_active_run, use_archive, use_runs};
+use crate::routes::Route;
+use dioxus::prelude::*;
+use std::time::Duration;
+use strum::IntoEnumIterator;
+use vulkan_cts_analyzer::stats::{
+    BINS_PER_DECADE, DurationSummary, MAX_DECADE, MIN_DECADE, duration_histogram, slowest,
+};
+
+const CHART_WIDTH: f32 = 800.0;
+const CHART_HEIGHT: f32 = 260.0;
+const CHART_PADDING: f32 = 40.0;
+
+const SLOWEST_COUNTS: [usize; 4] = [10, 25, 50, 100];
+
+// Sums of many long tests may not fit in a `Duration`
+fn format_duration(seconds: f32) -> String {
+    Duration::try_from_secs_f32(seconds)
+        .map_or_else(|_| "-".to_string(), |d| HMSDuration(d).to_string())
+}
+
+// Short axis labels, e.g. 1ms, 10s, 1000s
+fn format_decade(decade: i32) -> String {
+    if decade < 0 {
+        format!("{}ms", 10_u32.pow((decade + 3) as u32))
+    } else {
+        format!("{}s", 10_u32.pow(decade as u32))
+    }
+}
+
+#[component]
+pub fn Durations(results: String) -> Element {
+    use_archive(results).load("Loading CTS results")?;
+
+    let active_run = use_active_run();
+    let runs = use_runs();
+
+    // Indices of the slowest tests with a valid duration, enough for the longest list
+    let timings = use_memo(move || {
+        let count = SLOWEST_COUNTS[SLOWEST_COUNTS.len() - 1];
+        slowest(&active_run.read().results, count)
+    });
+
+    let summaries = use_memo(move || {
+        let run = active_run.read();
+        std::iter::once(None)
+            .chain(TestStatus::iter().map(Some))
+            .filter_map(|status| {
+                DurationSummary::new(&run.results, status).map(|summary| (status, summary))
+            })
+            .collect::<Vec<_>>()
+    });
+
+    let histogram = use_memo(move || duration_histogram(&active_run.read().results));
+
+    let mut slowest_count = use_signal(|| SLOWEST_COUNTS[0]);
+
+    let run = active_run.read();
+    let Some((_, overall)) = summaries.read().first().cloned() else {
+        return rsx! {
+            p { class: "text-center text-gray-400", "The active run has no test durations" }
+        };
+    };
+
+    rsx! {
+        div {
+            class: "flex flex-col space-y-4 rounded-3xl p-4 pt-8 w-full h-fit shadow-xl shadow-slate-950 text-gray-400",
+            style: "background: linear-gradient(145deg, #020617 0, #02081f 60%, #020617 100%);",
+            div { class: "grid grid-cols-1 sm:grid-cols-2 md:grid-cols-3 lg:grid-cols-4 xl:grid-cols-6 gap-4",
+                for (name , value) in [
+                    ("Summed duration", overall.total),
+                    ("Slowest test", overall.max),
+                    ("p50", overall.p50),
+                    ("p90", overall.p90),
+                    ("p99", overall.p99),
+                ]
+                {
+                    DurationCard { name: name.to_string(), value }
+                }
+                StatCard {
+                    name: "Timed tests".to_string(),
+                    color: "#38bdf8".to_string(),
+                    count: overall.count,
+                    stat: 100.0 * overall.count as f32 / run.results.len().max(1) as f32,
+                }
+            }
+            p { class: "text-xs text-gray-500",
+                "Durations are summed over every test: the results hold no timestamps, so the wall time of a parallel run is not known"
+            }
+            h3 { class: "text-sm text-gray-300", "Tests per duration (log scale)" }
+            Histogram { bins: histogram() }
+            h3 { class: "text-sm text-gray-300", "Percentiles per status (H:M:S.MS)" }
+            div { class: "w-full bg-gray-900 overflow-auto border-1 border-slate-700 rounded-lg",
+                table { class: "w-full border-collapse border-spacing-0",
+                    tr {
+                        class: "border-b-1 border-slate-700",
+                        style: "background: radial-gradient(circle at top, rgba(56, 189, 248, 0.1), rgba(15, 23, 42, 1));",
+                        for header in ["Status", "Tests", "p50", "p90", "p99", "Max", "Summed"] {
+                            th { class: "uppercase bold whitespace-nowrap py-2 px-3", "{header}" }
+                        }
+                    }
+                    for (status , summary) in summaries() {
+                        tr { class: "text-sm text-center hover:bg-[#38bef7]/5",
+                            td { class: "py-2 px-3",
+                                if let Some(status) = status {
+                                    StatusBadge { status }
+                                } else {
+                                    p { class: "font-bold text-gray-300", "All" }
+                                }
+                            }
+                            td { class: "py-2 px-3", "{summary.count}" }
+                            for value in [summary.p50, summary.p90, summary.p99, summary.max, summary.total] {
+                                td { class: "py-2 px-3", "{format_duration(value)}" }
+                            }
+                        }
+                    }
+                }
+            }
+            div { class: "flex flex-row items-center justify-between",
+                h3 { class: "text-sm text-gray-300", "Slowest tests" }
+                div { class: "flex flex-row gap-2",
+                    for count in SLOWEST_COUNTS {
+                        button {
+                            class: "pagination-button",
+                            "data-active": count == slowest_count(),
+                            onclick: move |_| slowest_count.set(count),
+                            "{count}"
+                        }
+                    }
+                }
+            }
+            div { class: "w-full bg-gray-900 overflow-auto border-1 border-slate-700 rounded-lg",
+                table { class: "w-full border-collapse border-spacing-0",
+                    for (i , test) in timings.read().iter().take(slowest_count()).map(|i| run.results.get(*i)).enumerate() {
+                        tr { class: "text-sm hover:bg-[#38bef7]/5",
+                            td { class: "py-2 px-3 text-slate-500", "{i + 1}" }
+                            td { class: "py-2 px-3",
+                                Link {
+                                    class: "hover:underline break-all",
+                                    to: Route::test(test.name, runs.read().results_query()),
+                                    "{test.name}"
+                                }
+                            }
+                            td { class: "py-2 px-3 whitespace-nowrap",
+                                if let Some(duration) = test.duration {
+                                    "{HMSDuration(duration)}"
+                                }
+                            }
+                            td { class: "py-2 px-3 w-32",
+                                StatusBadge { status: test.status }
+                            }
+                        }
+                    }
+                }
+            }
+        }
+    }
+}
+
+#[component]
+fn DurationCard(name: String, value: f32) -> Element {
+    rsx! {
+        div { class: "rounded-2xl p-4 border-1 border-slate-800 shadow-xl shadow-[#02081f] w-full h-fit bg-[#090f21] flex flex-col space-y-2",
+            h3 { class: "text-sm text-gray-300", "{name}" }
+            h2 { class: "text-2xl font-bold text-[#38bdf8]", "{format_duration(value)}" }
+            p { class: "text-xs text-gray-400", "H:M:S.MS" }
+        }
+    }
+}
+
+#[component]
+fn Histogram(bins: Vec<usize>) -> Element {
+    let max = bins.iter().copied().max().unwrap_or(0).max(1) as f32;
+    let width = (CHART_WIDTH - 2.0 * CHART_PADDING) / bins.len() as f32;
+    let height = move |count: usize| count as f32 / max * (CHART_HEIGHT - 2.0 * CHART_PADDING);
+
+    rsx! {
+        svg {
+            class: "w-full h-auto text-slate-500",
+            view_box: "0 0 {CHART_WIDTH} {CHART_HEIGHT}",
+            line {
+                x1: "{CHART_PADDING}",
+                x2: "{CHART_WIDTH - CHART_PADDING}",
+                y1: "{CHART_HEIGHT - CHART_PADDING}",
+                y2: "{CHART_HEIGHT - CHART_PADDING}",
+                stroke: "rgba(255, 255, 255, 0.1)",
+            }
+            text {
+                x: "{CHART_PADDING - 4.0}",
+                y: "{CHART_PADDING + 4.0}",
+                "text-anchor": "end",
+                "font-size": "10",
+                fill: "currentColor",
+                "{max}"
+            }
+            for (i , count) in bins.iter().copied().enumerate() {
+                rect {
+                    x: "{CHART_PADDING + i as f32 * width + 1.0}",
+                    y: "{CHART_HEIGHT - CHART_PADDING - height(count)}",
+                    width: "{width - 2.0}",
+                    height: "{height(count)}",
+                    fill: "#38bdf8",
+                    opacity: "0.8",
+                }
+            }
+            for decade in MIN_DECADE..=MAX_DECADE {
+                text {
+                    x: "{CHART_PADDING + (decade - MIN_DECADE) as f32 * BINS_PER_DECADE as f32 * width}",
+                    y: "{CHART_HEIGHT - CHART_PADDING / 2.0}",
+                    "text-anchor": "middle",
+                    "font-size": "10",
+                    fill: "currentColor",
+                    "{format_decade(decade)}"
+                }
+            }
+        }
+    }
+}
@@ -0,0 +1,84 @@
+use crate::landing::{Dashboard, LandingPlaceholder, TableState};
+use crate::loader::Loader;
+use crate::results::{use_archive, use_runs};
+use crate::routes::Route;
+use dioxus::prelude::*;
+
+/// Splits a group path into its segments, `/group/dEQP-VK/api` and `/group/dEQP-VK.api` are the same group
+pub fn group_segments(path: &[String]) -> Vec<String> {
+    path.iter()
+        .flat_map(|p| p.split('.'))
+        .filter(|s| !s.is_empty())
+        .map(str::to_string)
+        .collect()
+}
+
+#[component]
+pub fn Group(
+    path: Vec<String>,
+    results: String,
+    status: String,
+    search: String,
+    sort: String,
+    page: usize,
+    unexpected: bool,
+) -> Element {
+    let nav = use_navigator();
+
+    use_archive(results.clone()).load_with(rsx! {
+        LandingPlaceholder {}
+    })?;
+
+    let segments = group_segments(&path);
+    let prefix = format!("{}.", segments.join("."));
+
+    let onstate = move |state: TableState| {
+        nav.replace(Route::Group {
+            path: path.clone(),
+            results: results.clone(),
+            status: state.status_query(),
+            search: state.search_query(),
+            sort: state.sort_query(),
+            page: state.page_query(),
+            unexpected: state.unexpected_only,
+        });
+    };
+
+    rsx! {
+        div { class: "flex flex-col space-y-4",
+            Breadcrumbs { segments }
+            Dashboard {
+                prefix,
+                state: TableState::from_query(&status, &search, &sort, page, unexpected),
+                on_state: onstate,
+            }
+        }
+    }
+}
+
+#[component]
+pub fn Breadcrumbs(segments: Vec<String>) -> Element {
+    let runs = use_runs();
+
+    rsx! {
+        nav { class: "flex flex-row flex-wrap items-center gap-1 text-sm text-gray-400",
+            Link {
+                class: "hover:underline",
+                to: Route::home(runs.read().results_query()),
+                "All tests"
+            }
+            for (i , segment) in segments.iter().enumerate() {
+                span { class: "text-slate-600", "/" }
+                if i + 1 == segments.len() {
+                    span { class: "text-white font-bold", "{segment}" }
+                } else {
+                    Link {
+                        class: "hover:underline",
+                        to: Route::group(segments[..=i].to_vec(), runs.read().results_query()),
+                        "{segment}"
+                    }
+                }
+            }
+        }
+    }
+}
@@ -1,91 +1,101 @@
 use crate::components::{select::*, skeleton::*};
-use crate::loader::Loader;
-use csv::{ReaderBuilder, StringRecord};
+use crate::loader::{LoadProgressBar, Loader};
+use crate::results::{
+    Archive, HMSDuration, Runs, TestStatus, failure_statuses, status_counts, use_active_run,
+    use_archive, use_expectations, use_runs,
+};
+use crate::routes::Route;
+use dioxus::html::FileData;
 use dioxus::prelude::*;
 use dioxus_primitives::toast::{ToastOptions, use_toast};
 use dioxus_sdk_time::*;
 use std::collections::HashMap;
 use std::f32::consts::PI;
-use std::fmt;
 use std::str::FromStr;
 use std::time::Duration;
 use strum::{EnumCount, IntoEnumIterator};
+use vulkan_cts_analyzer::expectations::{Expectations, ExpectationsFile};
+use vulkan_cts_analyzer::query::Query;
+use vulkan_cts_analyzer::sort::{Column, SortKey, parse_sort, sort_query, sort_rows, toggle_sort};
+
+pub const PAGE_SIZE: usize = 100_usize;
+
+// Scroll view: every row has the same height so the visible ones can be computed from the scroll offset
+const ROW_HEIGHT: f64 = 45.0;
+const VIEWPORT_HEIGHT: f64 = 720.0;
+// Rows rendered above and below the viewport to avoid blank flashes while scrolling
+const OVERSCAN: usize = 10_usize;
+
+/// Filter, search and page of the results table, mirrored in the route query so a view can be shared as a link
+#[derive(Clone, Default, PartialEq)]
+pub struct TableState {
+    /// Statuses to show, every status when empty
+    pub status: Vec<TestStatus>,
+    pub search: Option<String>,
+    /// Sort keys, most significant first, CSV order when empty
+    pub sort: Vec<SortKey>,
+    pub page: usize,
+    /// Only the results the expectations do not account for
+    pub unexpected_only: bool,
+}
 
-const PAGE_SIZE: usize = 100_usize;
-
-// Wrapper for displaying a duration in h:m:s (integer seconds, rounded down)
-struct HMSDuration(Duration);
-impl fmt::Display for HMSDuration {
-    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
-        let mut ms = self.0.as_millis();
-        let hours = ms / 3_600_000;
-        ms %= 3_600_000;
-        let mins = ms / 60_000;
-        ms %= 60_000;
-        let secs = ms / 1000;
-        ms %= 1000;
-
-        if hours > 0 {
-            write!(f, "{}:{:02}:{:02}.{}", hours, mins, secs, ms)
-        } else if mins > 0 {
-            write!(f, "0:{}:{:02}.{}", mins, secs, ms)
-        } else {
-            write!(f, "0:0:{}.{}", secs, ms)
+impl TableState {
+    // Pages are counted from 1 in the URL, like in the page indicator
+    pub fn from_query(
+        status: &str,
+        search: &str,
+        sort: &str,
+        page: usize,
+        unexpected_only: bool,
+    ) -> Self {
+        Self {
+            status: status
+                .split(',')
+                .filter_map(|s| TestStatus::from_str(s).ok())
+                .collect(),
+            search: (!search.is_empty()).then(|| search.to_string()),
+            sort: parse_sort(sort),
+            page: page.saturating_sub(1),
+            unexpected_only,
         }
     }
-}
 
-#[derive(
-    Debug,
-    Eq,
-    Hash,
-    Clone,
-    Copy,
-    PartialEq,
-    strum::EnumCount,
-    strum::EnumIter,
-    strum::Display,
-    strum::EnumString,
-)]
-enum TestStatus {
-    Pass,
-    Fail,
-    Warn,
-    Skip,
-    Crash,
-    Timeout,
-}
+    pub fn status_query(&self) -> String {
+        self.status
+            .iter()
+            .map(|s| s.to_string())
+            .collect::<Vec<_>>()
+            .join(",")
+    }
 
-impl TestStatus {
-    const fn emoji(&self) -> &'static str {
-        match self {
-            TestStatus::Pass => "✅",
-            TestStatus::Fail => "❌",
-            TestStatus::Skip => "❎",
-            TestStatus::Timeout => "⏱️",
-            TestStatus::Warn => "⚠️",
-            TestStatus::Crash => "💥",
-        }
+    pub fn search_query(&self) -> String {
+        self.search.clone().unwrap_or_default()
     }
 
-    const fn color(&self) -> &'static str {
-        match self {
-            TestStatus::Pass => "#22c55e",
-            TestStatus::Fail => "#ff6467",
-            TestStatus::Skip => "#38bdf8",
-            TestStatus::Timeout => "#F77600",
-            TestStatus::Warn => "#ffdf20",
-            TestStatus::Crash => "#e7000b",
-        }
+    pub fn sort_query(&self) -> String {
+        sort_query(&self.sort)
+    }
+
+    pub fn page_query(&self) -> usize {
+        self.page + 1
     }
 }
 
-fn percentage(count: usize, total: f32) -> f32 {
+pub fn percentage(count: usize, total: f32) -> f32 {
     (count as f32 * 100.0) / total
 }
 
+// Tooltip of the expectation mark of a row
+fn expectation_title(expectations: &Expectations, name: &str, status: TestStatus) -> String {
+    if expectations.is_expected(name, status) {
+        return "Expected".to_string();
+    }
+    let expected = expectations.expected(name).unwrap_or(TestStatus::Pass);
+    format!("Unexpected, expected {expected}")
+}
+
 #[component]
-fn LandingPlaceholder() -> Element {
+pub fn LandingPlaceholder() -> Element {
     let stats_cards = TestStatus::iter().map(|s| {
         rsx! {
             StatCardPlaceholder {
@@ -110,6 +120,7 @@ fn LandingPlaceholder() -> Element {
         div {
             class: "flex flex-col space-y-4 rounded-3xl p-4 pt-8 w-full h-fit shadow-xl shadow-slate-950",
             style: "background: linear-gradient(145deg, #020617 0, #02081f 60%, #020617 100%);",
+            LoadProgressBar {}
             div { class: "flex flex-row space-x-4",
                 div { class: "border-1 border-[#38bdf8] bg-[#38bdf8]/15 text-slate-400 w-fit rounded-3xl py-1 px-2 flex flex-row space-x-1 items-center",
                     div { class: "bg-[#38bdf8] rounded-full size-3" }
@@ -123,7 +134,10 @@ fn LandingPlaceholder() -> Element {
             p { class: "text-xs text-slate-400",
                 "Skipped tests are mostly composed of unsupported features/extensions or Vulkan versions; they are not considered failures."
             }
-            div { class: "grid grid-cols-1 sm:grid-cols-2 md:grid-cols-3 lg:grid-cols-5 gap-4",
+            p { class: "text-xs text-slate-400",
+                "Failures are {failure_statuses()}; every other status counts as a success."
+            }
+            div { class: "grid grid-cols-1 sm:grid-cols-2 md:grid-cols-3 lg:grid-cols-4 xl:grid-cols-6 gap-4",
                 {stats_cards}
             }
             div { class: "mx-auto size-[200px]",
@@ -196,65 +210,144 @@ fn LandingPlaceholder() -> Element {
 }
 
 #[component]
-pub fn Landing() -> Element {
+pub fn Landing(
+    results: String,
+    status: String,
+    search: String,
+    sort: String,
+    page: usize,
+    unexpected: bool,
+) -> Element {
     let toast = use_toast();
+    let nav = use_navigator();
 
-    let resource = use_resource(move || {
-        toast.info(
-            "Loading...".to_string(),
-            ToastOptions::new()
-                .description("Loading CTS results")
-                .duration(Duration::from_secs(12)),
-        );
-        get_results()
-    })
-    .load_with(rsx! {
+    let mut runs = use_runs();
+
+    use_archive(results.clone()).load_with(rsx! {
         LandingPlaceholder {}
     })?;
-    let mut result = use_signal(Vec::<StringRecord>::new);
-
-    use_effect(move || match &*resource.read() {
-        Ok(results) => {
-            let mut reader = ReaderBuilder::new().from_reader(results.as_bytes());
-            let mut records = match reader
-                .into_records()
-                .collect::<Result<Vec<StringRecord>, csv::Error>>()
-            {
-                Ok(res) => res,
-                Err(e) => {
-                    error!("Failed to parse results: {e}");
-                    toast.error(
-                        "Error".to_string(),
-                        ToastOptions::new().description("Failed to parse CTS results"),
-                    );
-                    Vec::new()
-                }
-            };
-            records.retain(|record| TestStatus::from_str(&record[1]).is_ok());
-            result.set(records);
-            toast.success(
-                "Success".to_string(),
-                ToastOptions::new().description("Successfully loaded CTS results"),
-            );
+
+    let onfile = move |(name, bytes): (String, Vec<u8>)| async move {
+        match Archive::open(name.clone(), bytes).await {
+            Ok(archive) => {
+                let requested = runs.peek().results_query();
+                runs.set(Runs::new(requested, archive));
+            }
+            Err(e) => {
+                error!("Failed to read {name}: {e}");
+                toast.error(
+                    "Error".to_string(),
+                    ToastOptions::new().description(format!("Failed to read {name}")),
+                );
+            }
+        }
+    };
+
+    let onstate = move |state: TableState| {
+        nav.replace(Route::Landing {
+            results: results.clone(),
+            status: state.status_query(),
+            search: state.search_query(),
+            sort: state.sort_query(),
+            page: state.page_query(),
+            unexpected: state.unexpected_only,
+        });
+    };
+
+    rsx! {
+        Dashboard {
+            prefix: String::new(),
+            state: TableState::from_query(&status, &search, &sort, page, unexpected),
+            on_state: onstate,
+            ResultsDropZone { on_file: onfile }
+            ExpectationsExport {}
         }
-        Err(e) => {
-            error!("Failed to fetch results: {e}");
-            toast.error(
-                "Error".to_string(),
-                ToastOptions::new().description("Failed to fetch CTS results"),
-            );
+    }
+}
+
+/// Downloads the fails, flakes and skips lists updated from the active run, and the patterns of
+/// its failures
+#[component]
+fn ExpectationsExport() -> Element {
+    let active_run = use_active_run();
+    let expectations = use_expectations();
+    let mut collapse = use_signal(|| false);
+
+    let download = move |name: String, text: String| {
+        let eval = document::eval(
+            r#"
+            const [name, text] = await dioxus.recv();
+            const url = URL.createObjectURL(new Blob([text], { type: "text/plain" }));
+            const link = document.createElement("a");
+            link.href = url;
+            link.download = name;
+            link.click();
+            URL.revokeObjectURL(url);
+            "#,
+        );
+        if let Err(e) = eval.send((&name, text)) {
+            error!("Failed to export {name}: {e}");
         }
-    });
+    };
+    let export = move |file: ExpectationsFile| {
+        let text = expectations
+            .read()
+            .export(file, &active_run.read().results, collapse());
+        download(format!("{file}.txt"), text);
+    };
+    let export_patterns = move |_| {
+        let text = expectations
+            .read()
+            .fail_patterns(&active_run.read().results, collapse());
+        download("fail-patterns.txt".to_string(), text);
+    };
 
-    let global_stats = use_memo(move || {
-        result.read().iter().fold(
-            HashMap::from_iter(TestStatus::iter().map(|s| (s, 0))),
-            |mut acc, record| {
-                if let Ok(status) = TestStatus::from_str(&record[1]) {
-                    *acc.entry(status).or_insert(0) += 1;
+    rsx! {
+        div { class: "flex flex-row flex-wrap items-center gap-3 text-xs text-slate-400",
+            p { "Export the active run as deqp-runner lists:" }
+            for file in [ExpectationsFile::Fails, ExpectationsFile::Flakes, ExpectationsFile::Skips] {
+                button {
+                    class: "pagination-button",
+                    onclick: move |_| export(file),
+                    "{file}.txt"
                 }
-                acc
-            },
+            }
+            button { class: "pagination-button", onclick: export_patterns, "fail-patterns.txt" }
+            label { class: "flex flex-row items-center space-x-1 cursor-pointer select-none",
+                input {
+                    r#type: "checkbox",
+                    checked: collapse(),
+                    onchange: move |e: FormEvent| collapse.set(e.checked()),
+                }
+                span { "Collapse failing, flaky and skipped siblings into group regexes" }
+            }
+        }
+    }
+}
+
+/// Stats, pie chart and results table of the active run, limited to the tests under `prefix`
+#[component]
+pub fn Dashboard(
+    prefix: ReadSignal<String>,
+    state: ReadSignal<TableState>,
+    on_state: EventHandler<TableState>,
+    children: Element,
+) -> Element {
+    let active_run = use_active_run();
+    let expectations = use_expectations();
+
+    let global_stats = use_memo(move || {
+        let prefix = prefix.read();
+        if prefix.is_empty() {
+            return active_run.read().results.status_counts();
+        }
+        status_counts(
+            active_run
+                .read()
+                .results
+                .iter()
+                .filter(|t| t.name.starts_with(prefix.as_str()))
+                .map(|t| t.status),
         )
     });
 
@@ -265,6 +358,27 @@ pub fn Landing() -> Element {
             .fold(0.0_f32, |acc, (_, v)| acc + *v as f32)
     });
 
+    // Seeded from the route and written back to it, following links and history navigation
+    let mut current_page = use_memo(move || state.read().page);
+    let mut search_input = use_memo(move || state.read().search.clone());
+    let mut search_name = use_memo(move || state.read().search.clone());
+    let mut filter = use_memo(move || state.read().status.clone());
+    let mut sort = use_memo(move || state.read().sort.clone());
+    let mut unexpected_only = use_memo(move || state.read().unexpected_only);
+
+    let mut toggle_status = move |status: TestStatus| {
+        let mut statuses = filter();
+        if let Some(i) = statuses.iter().position(|s| *s == status) {
+            statuses.remove(i);
+        } else {
+            statuses.push(status);
+            // Keep the URL stable whatever the click order
+            statuses.sort_by_key(|s| *s as usize);
+        }
+        filter.set(statuses);
+        current_page.set(0_usize);
+    };
+
     let stats_cards = TestStatus::iter().map(|s| {
         rsx! {
             StatCard {
@@ -272,95 +386,130 @@ pub fn Landing() -> Element {
                 color: s.color().to_string(),
                 count: global_stats.read()[&s],
                 stat: percentage(global_stats.read()[&s], total()),
+                active: filter.read().contains(&s),
+                onclick: move |_| toggle_status(s),
             }
         }
     });
 
-    let mut current_page = use_memo(move || 0_usize);
-    let mut search_input: Signal<Option<String>> = use_signal(|| None);
-    let mut search_name: Signal<Option<String>> = use_signal(|| None);
-    let mut filter: Signal<Option<TestStatus>> = use_signal(|| None);
-    let filtered_count = use_memo(move || {
-        let f = filter();
-        let search = search_name();
-        let rows = result.read();
-
-        current_page.set(0_usize);
-
-        let mut total = 0_usize;
-
-        for r in rows.iter() {
-            let Ok(status) = TestStatus::from_str(&r[1]) else {
-                continue;
-            };
-            if let Some(wanted) = f {
-                if status != wanted {
-                    continue;
-                }
-            }
-
-            let name = &r[0];
-            if let Some(ref s) = search {
-                if !name.contains(s) {
-                    continue;
-                }
-            }
-
-            total += 1;
+    use_effect(move || {
+        let new_state = TableState {
+            status: filter(),
+            search: search_name(),
+            sort: sort(),
+            page: current_page(),
+            unexpected_only: unexpected_only(),
+        };
+        if *state.peek() != new_state {
+            on_state.call(new_state);
         }
-
-        total
     });
-    let mut page_count = use_memo(move || filtered_count().max(PAGE_SIZE - 1) / PAGE_SIZE);
-    let page = use_memo(move || {
-        let _ = *filtered_count.read();
 
-        let f = filter();
-        let search = search_name();
-        let shift = current_page() * PAGE_SIZE;
-
-        let rows = result.read();
-
-        let mut idx = 0_usize;
-        let mut out = Vec::with_capacity(PAGE_SIZE);
+    // Results under the prefix that the expectations do not account for, `None` without expectations
+    let unexpected_count = use_memo(move || {
+        let expectations = expectations.read();
+        if expectations.is_empty() {
+            return None;
+        }
+        let prefix = prefix.read();
+        Some(
+            active_run
+                .read()
+                .results
+                .iter()
+                .filter(|t| t.name.starts_with(prefix.as_str()))
+                .filter(|t| expectations.is_unexpected(t.name, t.status))
+                .count(),
+        )
+    });
 
-        for r in rows.iter() {
-            let name = &r[0];
+    let query_error = use_memo(move || search_name().and_then(|s| Query::parse(&s).err()));
 
-            if let Some(ref s) = search {
-                if !name.contains(s) {
-                    continue;
-                }
+    // Indices of the results matching the filter and the search, in CSV order
+    let filtered = use_memo(move || {
+        let f = filter();
+        // An invalid query is reported next to the search box and ignored
+        let query = search_name().and_then(|s| Query::parse(&s).ok());
+        let expectations = expectations.read();
+        let unexpected_only = unexpected_only() && !expectations.is_empty();
+        let prefix = prefix.read();
+        let run = active_run.read();
+        let results = &run.results;
+
+        let mut out = Vec::new();
+
+        for i in 0..results.len() {
+            // The status column is checked first, it is the cheapest
+            let status = results.status(i);
+            if !f.is_empty() && !f.contains(&status) {
+                continue;
             }
 
-            let Ok(status) = TestStatus::from_str(&r[1]) else {
+            let name = results.name(i);
+            if !name.starts_with(prefix.as_str()) {
                 continue;
-            };
+            }
 
-            if let Some(wanted) = f {
-                if status != wanted {
-                    continue;
-                }
+            if unexpected_only && !expectations.is_unexpected(name, status) {
+                continue;
             }
 
-            if idx >= shift && idx < shift + PAGE_SIZE {
-                out.push(r.clone());
-                if out.len() == PAGE_SIZE {
-                    break;
+            if let Some(ref query) = query {
+                if !query.matches(name, status, results.seconds(i)) {
+                    continue;
                 }
             }
 
-            idx += 1;
+            out.push(i);
         }
 
         out
     });
+    // Sorting happens before pagination, on every filtered row
+    let sorted = use_memo(move || {
+        let mut rows = filtered();
+        sort_rows(&active_run.read().results, &mut rows, &sort.read());
+        rows
+    });
+    let filtered_count = use_memo(move || filtered.read().len());
+    let mut page_count = use_memo(move || filtered_count().max(PAGE_SIZE - 1) / PAGE_SIZE);
+    let page = use_memo(move || {
+        let shift = current_page() * PAGE_SIZE;
+        sorted
+            .read()
+            .iter()
+            .skip(shift)
+            .take(PAGE_SIZE)
+            .copied()
+            .collect::<Vec<_>>()
+    });
+
+    let mut scroll_view = use_signal(|| false);
+    let mut scroll_top = use_signal(|| 0.0_f64);
+    // Rows in view in the scroll view, and the index of the first one
+    let visible = use_memo(move || {
+        let rows = sorted.read();
+        let last =
+            (((scroll_top() + VIEWPORT_HEIGHT) / ROW_HEIGHT) as usize + OVERSCAN).min(rows.len());
+        let first = ((scroll_top() / ROW_HEIGHT) as usize)
+            .saturating_sub(OVERSCAN)
+            .min(last);
+        (first, rows[first..last].to_vec())
+    });
+
+    let mut onsort = move |column: Column, event: MouseEvent| {
+        let keys = toggle_sort(&sort.read(), column, event.modifiers().shift());
+        sort.set(keys);
+        current_page.set(0_usize);
+    };
 
     let statuses = TestStatus::iter().enumerate().map(|(i, s)| {
         rsx! {
             SelectOption::<Option<TestStatus>> { index: i, value: s, text_value: "{s}",
                 {format!("{} {s}", s.emoji())}
-                SelectItemIndicator {}
+                if filter.read().contains(&s) {
+                    span { class: "ml-auto", "✓" }
+                }
             }
         }
     });
@@ -369,6 +518,7 @@ pub fn Landing() -> Element {
     let timeout = use_timeout(Duration::from_secs(1), move |()| {
         search_timeout.set(None);
         search_name.set(search_input());
+        current_page.set(0_usize);
     });
 
     let onsearch_input = move |event: FormEvent| {
@@ -391,6 +541,8 @@ pub fn Landing() -> Element {
         div {
             class: "flex flex-col space-y-4 rounded-3xl p-4 pt-8 w-full h-fit shadow-xl shadow-slate-950",
             style: "background: linear-gradient(145deg, #020617 0, #02081f 60%, #020617 100%);",
+            // Rows show up while the rest of the run is still being parsed
+            LoadProgressBar {}
             div { class: "flex flex-col sm:flex-row space-y-2 sm:space-y-0 justify-between",
                 div { class: "flex flex-row space-x-4",
                     div { class: "border-1 border-[#38bdf8] bg-[#38bdf8]/15 text-slate-400 w-fit rounded-3xl py-1 px-2 flex flex-row space-x-1 items-center",
@@ -401,6 +553,12 @@ pub fn Landing() -> Element {
                         div { class: "bg-[#22c55e] rounded-full size-3" }
                         p { class: "text-xs", "Filtered: {filtered_count} tests" }
                     }
+                    if let Some(count) = unexpected_count() {
+                        div { class: "border-1 border-[#ff6467] bg-[#ff6467]/15 text-slate-400 w-fit rounded-3xl py-1 px-2 flex flex-row space-x-1 items-center",
+                            div { class: "bg-[#ff6467] rounded-full size-3" }
+                            p { class: "text-xs", "Unexpected: {count} tests" }
+                        }
+                    }
                 }
                 p { class: "text-xs text-slate-400 my-auto", "{compile_time::date_str!()}" }
 
@@ -408,103 +566,255 @@ pub fn Landing() -> Element {
             p { class: "text-xs text-slate-400",
                 "Skipped tests are mostly composed of unsupported features/extensions or Vulkan versions; they are not considered failures."
             }
-            div { class: "grid grid-cols-1 sm:grid-cols-2 md:grid-cols-3 lg:grid-cols-5 gap-4",
+            p { class: "text-xs text-slate-400",
+                "Failures are {failure_statuses()}; every other status counts as a success."
+            }
+            {children}
+            div { class: "grid grid-cols-1 sm:grid-cols-2 md:grid-cols-3 lg:grid-cols-4 xl:grid-cols-6 gap-4",
                 {stats_cards}
             }
-            div { class: "mx-auto size-[200px]",
-                StatsPieChart { stats: global_stats, total }
+            div { class: "mx-auto w-[200px] flex flex-col space-y-2",
+                StatsPieChart {
+                    stats: global_stats,
+                    total,
+                    selected: filter(),
+                    on_toggle: toggle_status,
+                }
             }
             div { class: "mt-12 w-full flex flex-col md:flex-row justify-between text-gray-400 text-sm gap-4 items-center",
                 input {
                     class: "hidden lg:block w-full border-1 border-gray-700 px-3 py-1 rounded-lg text-sm",
                     style: "background: radial-gradient(circle at top, rgba(56, 189, 248, 0.1), rgba(15, 23, 42, 1));",
                     r#type: "search",
-                    placeholder: "Search tests...",
+                    placeholder: "Search tests, e.g. status:fail,crash -name:~wsi duration:>5s",
+                    value: search_input().unwrap_or_default(),
                     oninput: onsearch_input,
                     onkeyup: onsearch_keyup,
                 }
-                p { class: "my-auto w-fit text-nowrap",
-                    "Page {current_page() + 1} of {page_count() + 1}"
-                }
-                div { class: "hidden lg:block",
-                    Pagination { current_page, page_count, small: false }
+                if scroll_view() {
+                    p { class: "my-auto w-fit text-nowrap", "{filtered_count} rows" }
+                } else {
+                    p { class: "my-auto w-fit text-nowrap",
+                        "Page {current_page() + 1} of {page_count() + 1}"
+                    }
+                    div { class: "hidden lg:block",
+                        Pagination { current_page, page_count, small: false }
+                    }
+                    div { class: "block lg:hidden",
+                        Pagination { current_page, page_count, small: true }
+                    }
                 }
-                div { class: "block lg:hidden",
-                    Pagination { current_page, page_count, small: true }
+                button {
+                    class: "pagination-button text-nowrap",
+                    onclick: move |_| scroll_view.toggle(),
+                    if scroll_view() {
+                        "Paged view"
+                    } else {
+                        "Scroll view"
+                    }
                 }
             }
             input {
                 class: "block lg:hidden w-full border-1 border-gray-700 px-3 py-1 rounded-lg text-sm",
                 style: "background: radial-gradient(circle at top, rgba(56, 189, 248, 0.1), rgba(15, 23, 42, 1));",
                 r#type: "search",
-                placeholder: "Search tests...",
+                placeholder: "Search tests, e.g. status:fail,crash -name:~wsi duration:>5s",
+                value: search_input().unwrap_or_default(),
                 oninput: onsearch_input,
                 onkeyup: onsearch_keyup,
             }
-            div { class: "w-full bg-gray-900 overflow-auto border-1 border-slate-700 rounded-lg text-gray-400",
+            if let Some(e) = query_error() {
+                p { class: "text-xs text-[#ff6467] font-mono whitespace-pre-wrap", "{e}" }
+            }
+            div {
+                class: "w-full bg-gray-900 overflow-auto border-1 border-slate-700 rounded-lg text-gray-400",
+                style: if scroll_view() { format!("max-height: {VIEWPORT_HEIGHT}px;") },
+                onscroll: move |e: ScrollEvent| {
+                    if scroll_view() {
+                        scroll_top.set(e.data().scroll_top());
+                    }
+                },
                 table { class: "w-full border-collapse border-spacing-0",
                     tr {
-                        class: "border-b-1 border-slate-700",
-                        style: "background: radial-gradient(circle at top, rgba(56, 189, 248, 0.1), rgba(15, 23, 42, 1));",
-                        th { class: "text-left uppercase bold whitespace-nowrap py-2 px-3",
+                        class: "border-b-1 border-slate-700 sticky top-0 z-10",
+                        style: "background: radial-gradient(circle at top, rgba(56, 189, 248, 0.1), rgba(15, 23, 42, 1)), #101828;",
+                        th {
+                            class: "text-left uppercase bold whitespace-nowrap py-2 px-3 cursor-pointer select-none",
+                            title: "Sort, shift-click to sort on several columns",
+                            onclick: move |e| onsort(Column::Name, e),
                             "Test name"
+                            SortIndicator { keys: sort(), column: Column::Name }
                         }
-                        th { class: "text-left uppercase bold whitespace-nowrap py-2 px-3",
+                        th {
+                            class: "text-left uppercase bold whitespace-nowrap py-2 px-3 cursor-pointer select-none",
+                            title: "Sort, shift-click to sort on several columns",
+                            onclick: move |e| onsort(Column::Duration, e),
                             "Duration (H:M:S.MS)"
+                            SortIndicator { keys: sort(), column: Column::Duration }
                         }
                         th { class: "uppercase bold whitespace-nowrap py-2 px-3",
-                            Select::<Option<TestStatus>> {
-                                on_value_change: move |value: Option<Option<TestStatus>>| filter.set(value.unwrap_or(None)),
-                                SelectTrigger {
-                                    class: "select-trigger mx-auto w-fit !bg-transparent !shadow-none !text-gray-400 cursor-pointer uppercase",
-                                    aria_label: "Select Trigger",
-                                    SelectValue {
-                                        class: "!bg-transparent !shadow-none !text-gray-400",
-                                        placeholder: "STATUS",
-                                    }
+                            div { class: "flex flex-row items-center justify-center",
+                                button {
+                                    class: "cursor-pointer select-none px-1",
+                                    title: "Sort, shift-click to sort on several columns",
+                                    onclick: move |e| onsort(Column::Status, e),
+                                    "⇅"
+                                    SortIndicator { keys: sort(), column: Column::Status }
                                 }
-                                SelectList { aria_label: "Select status",
-                                    SelectGroup {
-                                        {statuses}
-                                        SelectOption::<Option<TestStatus>> {
-                                            index: TestStatus::COUNT,
-                                            value: None,
-                                            text_value: "Status",
-                                            "🔄 None"
-                                            SelectItemIndicator {}
+                                // Picking a status toggles it in the filter, the select itself never keeps a value
+                                Select::<Option<TestStatus>> {
+                                    value: Some(None),
+                                    on_value_change: move |value: Option<Option<TestStatus>>| match value.flatten() {
+                                        Some(status) => toggle_status(status),
+                                        None => {
+                                            filter.set(Vec::new());
+                                            current_page.set(0_usize);
                                         }
-                                    }
-                                }
-                            }
-                        }
-                    }
-                    for test in page.iter() {
-                        if let Ok(status) = TestStatus::from_str(&test[1]) {
-                            tr { class: "text-sm hover:bg-[#38bef7]/5",
-                                td { class: "py-2 px-3", "{&test[0]}" }
-                                td { class: "py-2 px-3",
-                                    p { class: "mx-auto w-fit",
-                                        if let Ok(duration) = test[2].parse::<f32>() {
-                                            "{HMSDuration(Duration::from_secs_f32(duration))}"
+                                    },
+                                    SelectTrigger {
+                                        class: "select-trigger mx-auto w-fit !bg-transparent !shadow-none !text-gray-400 cursor-pointer uppercase",
+                                        aria_label: "Select Trigger",
+                                        if filter.read().is_empty() {
+                                            "Status"
                                         } else {
-                                            "Invalid data"
+                                            "Status ({filter.read().len()})"
+                                        }
+                                    }
+                                    SelectList { aria_label: "Select status",
+                                        SelectGroup {
+                                            {statuses}
+                                            SelectOption::<Option<TestStatus>> {
+                                                index: TestStatus::COUNT,
+                                                value: None,
+                                                text_value: "Status",
+                                                "🔄 All"
+                                            }
                                         }
                                     }
                                 }
-                                td { class: "py-2 px-3",
-                                    StatusBadge {
-                                        status
+                                if unexpected_count().is_some() {
+                                    button {
+                                        class: "cursor-pointer select-none px-1 text-xs normal-case text-nowrap data-[active=true]:text-[#ff6467]",
+                                        "data-active": unexpected_only(),
+                                        title: "Only show the Fail, Crash and Timeout results the fails, flakes and skips lists do not account for",
+                                        onclick: move |_| {
+                                            unexpected_only.set(!unexpected_only());
+                                            current_page.set(0_usize);
+                                        },
+                                        "Unexpected only"
                                     }
                                 }
                             }
                         }
                     }
+                    if scroll_view() {
+                        // Spacers stand in for the rows out of view so the scrollbar covers the whole set
+                        tr { style: format!("height: {}px;", visible().0 as f64 * ROW_HEIGHT) }
+                        for index in visible().1 {
+                            ResultRow { key: "{index}", index, fixed_height: true }
+                        }
+                        tr {
+                            style: format!(
+                                "height: {}px;",
+                                filtered_count().saturating_sub(visible().0 + visible().1.len()) as f64 * ROW_HEIGHT,
+                            ),
+                        }
+                    } else {
+                        for index in page() {
+                            ResultRow { key: "{index}", index, fixed_height: false }
+                        }
+                    }
                 }
             }
         }
     }
 }
 
+/// A row of the results table, `fixed_height` keeps names on one line for the scroll view
+#[component]
+fn ResultRow(index: usize, fixed_height: bool) -> Element {
+    let active_run = use_active_run();
+    let expectations = use_expectations();
+    let runs = use_runs();
+    let run = active_run.read();
+    if index >= run.results.len() {
+        return rsx! {};
+    }
+    let test = run.results.get(index);
+    let expectation = {
+        let expectations = expectations.read();
+        (!expectations.is_empty()).then(|| {
+            (
+                expectations.is_expected(test.name, test.status),
+                expectation_title(&expectations, test.name, test.status),
+            )
+        })
+    };
+
+    rsx! {
+        tr {
+            class: "text-sm hover:bg-[#38bef7]/5",
+            style: if fixed_height { format!("height: {ROW_HEIGHT}px;") },
+            td {
+                class: "py-2 px-3 data-[fixed=true]:whitespace-nowrap",
+                "data-fixed": fixed_height,
+                Link {
+                    class: "hover:underline",
+                    to: Route::test(test.name, runs.read().results_query()),
+                    "{test.name}"
+                }
+            }
+            td { class: "py-2 px-3",
+                p { class: "mx-auto w-fit whitespace-nowrap",
+                    if let Some(duration) = test.duration {
+                        "{HMSDuration(duration)}"
+                    } else {
+                        "Invalid data"
+                    }
+                }
+            }
+            td { class: "py-2 px-3",
+                div { class: "flex flex-row items-center justify-center space-x-2",
+                    StatusBadge { status: test.status }
+                    if let Some((expected, title)) = expectation {
+                        span {
+                            class: "w-4 text-xs select-none",
+                            style: if expected { "color: #94a3b8;" } else { "color: #ff6467;" },
+                            title,
+                            if expected {
+                                "✓"
+                            } else {
+                                "⚠"
+                            }
+                        }
+                    }
+                }
+            }
+        }
+    }
+}
+
+/// Direction of a sorted column, with its priority when sorting on several columns
+#[component]
+fn SortIndicator(keys: Vec<SortKey>, column: Column) -> Element {
+    let Some(i) = keys.iter().position(|key| key.column == column) else {
+        return rsx! {};
+    };
+
+    rsx! {
+        span { class: "ml-1 text-[#38bdf8]",
+            if keys[i].descending {
+                "▼"
+            } else {
+                "▲"
+            }
+            if keys.len() > 1 {
+                sup { "{i + 1}" }
+            }
+        }
+    }
+}
+
 #[component]
 fn StatCardPlaceholder(name: String, color: String, count: usize, stat: f32) -> Element {
     rsx! {
@@ -522,10 +832,28 @@ fn StatCardPlaceholder(name: String, color: String, count: usize, stat: f32) ->
     }
 }
 
+/// A status count, clickable when `onclick` is set and highlighted when `active`
 #[component]
-fn StatCard(name: String, color: String, count: usize, stat: f32) -> Element {
+pub fn StatCard(
+    name: String,
+    color: String,
+    count: usize,
+    stat: f32,
+    #[props(default)] active: bool,
+    onclick: Option<EventHandler<MouseEvent>>,
+) -> Element {
+    let clickable = onclick.is_some();
+
     rsx! {
-        div { class: "rounded-2xl p-4 border-1 border-slate-800 shadow-xl shadow-[#02081f] w-full h-fit bg-[#090f21] flex flex-col space-y-2",
+        div {
+            class: "rounded-2xl p-4 border-1 border-slate-800 shadow-xl shadow-[#02081f] w-full h-fit bg-[#090f21] flex flex-col space-y-2 data-[clickable=true]:cursor-pointer data-[clickable=true]:hover:border-slate-600",
+            style: if active { format!("border-color: {color}; background-color: {color}26;") },
+            "data-clickable": clickable,
+            onclick: move |e| {
+                if let Some(onclick) = onclick {
+                    onclick.call(e);
+                }
+            },
             div { class: "flex flex-row space-x-2 flex items-center",
                 div {
                     class: "rounded-full size-4",
@@ -544,7 +872,7 @@ fn StatCard(name: String, color: String, count: usize, stat: f32) -> Element {
 }
 
 #[component]
-fn StatusBadge(status: TestStatus) -> Element {
+pub fn StatusBadge(status: TestStatus) -> Element {
     rsx! {
         div {
             class: "mx-auto border-1 py-1 px-3 rounded-3xl text-xs w-fit select-none",
@@ -564,14 +892,30 @@ fn StatusBadge(status: TestStatus) -> Element {
 }
 
 #[component]
-fn StatsPieChart(stats: ReadSignal<HashMap<TestStatus, usize>>, total: ReadSignal<f32>) -> Element {
+fn StatsPieChart(
+    stats: ReadSignal<HashMap<TestStatus, usize>>,
+    total: ReadSignal<f32>,
+    selected: ReadSignal<Vec<TestStatus>>,
+    on_toggle: EventHandler<TestStatus>,
+) -> Element {
     struct Segment {
+        status: TestStatus,
         percentage: f32,
         start: f32,
         end: f32,
         color: String,
     }
 
+    let unskipped_total = use_memo(move || total() - *stats.read().get(&TestStatus::Skip).unwrap_or(&0) as f32);
+    let failed = use_memo(move || {
+        stats
+            .read()
+            .iter()
+            .filter(|(s, _)| s.is_failure())
+            .fold(0_usize, |acc, (_, v)| acc + *v)
+    });
+
+    // After the hooks, so that every render calls the same ones
     if total() == 0.0 {
         return rsx! {};
     }
@@ -579,15 +923,19 @@ fn StatsPieChart(stats: ReadSignal<HashMap<TestStatus, usize>>, total: ReadSigna
     let mut segments: Vec<Segment> = Vec::new();
     let mut cumulative = 0.0_f32;
 
-    let unskipped_total = use_memo(move || total() - *stats.read().get(&TestStatus::Skip).unwrap_or(&0) as f32);
+    // Skipped tests are left out of the chart, successes come first and failures are
+    // grouped at the end so the red part of the chart reads as the failure rate
+    let mut ordered = TestStatus::iter()
+        .filter(|s| *s != TestStatus::Skip)
+        .collect::<Vec<_>>();
+    ordered.sort_by_key(TestStatus::is_failure);
 
-    for (key, val) in stats.read().iter() {
-        if *key == TestStatus::Skip {
-            continue;
-        }
-        let stat = *val as f32 / unskipped_total();
+    for key in ordered {
+        let val = *stats.read().get(&key).unwrap_or(&0);
+        let stat = val as f32 / unskipped_total();
         if stat > 0.0 {
             segments.push(Segment {
+                status: key,
                 percentage: stat * 100.0,
                 start: cumulative,
                 end: cumulative + stat,
@@ -602,6 +950,14 @@ fn StatsPieChart(stats: ReadSignal<HashMap<TestStatus, usize>>, total: ReadSigna
     let cy: f32 = 100.0;
 
     let paths = segments.iter().enumerate().map(|(idx, seg)| {
+        let status = seg.status;
+        // Slices out of the filter are dimmed
+        let opacity = if selected.read().is_empty() || selected.read().contains(&status) {
+            "0.9"
+        } else {
+            "0.3"
+        };
+
         if (seg.end - seg.start) >= 1.0 - f32::EPSILON {
             return rsx! {
                 circle {
@@ -610,9 +966,11 @@ fn StatsPieChart(stats: ReadSignal<HashMap<TestStatus, usize>>, total: ReadSigna
                     cy: "{cy}",
                     r: "{radius}",
                     fill: "{seg.color}",
-                    opacity: "0.9",
+                    opacity,
                     stroke: "rgba(255, 255, 255, 0.1)",
                     "stroke-width": "1",
+                    class: "cursor-pointer",
+                    onclick: move |_| on_toggle.call(status),
                 }
             };
         }
@@ -635,20 +993,31 @@ fn StatsPieChart(stats: ReadSignal<HashMap<TestStatus, usize>>, total: ReadSigna
                 key: "{idx}",
                 d: "{d}",
                 fill: "{seg.color}",
-                opacity: "0.9",
+                opacity,
                 stroke: "rgba(255, 255, 255, 0.1)",
                 "stroke-width": "1",
+                class: "cursor-pointer",
+                onclick: move |_| on_toggle.call(status),
             }
         }
     });
 
     rsx! {
         svg { class: "max-w-[200px] h-auto", view_box: "0 0 200 200", {paths} }
+        if unskipped_total() > 0.0 {
+            p { class: "text-xs text-slate-400 text-center",
+                "{percentage(failed(), unskipped_total()):.1}% failures (skipped tests excluded)"
+            }
+        }
     }
 }
 
 #[component]
-fn Pagination(current_page: Memo<usize>, page_count: ReadSignal<usize>, small: bool) -> Element {
+pub fn Pagination(
+    current_page: Memo<usize>,
+    page_count: ReadSignal<usize>,
+    small: bool,
+) -> Element {
     let range = if small { 1_usize } else { 2_usize };
 
     rsx! {
@@ -766,16 +1135,72 @@ fn PaginationPlaceholder(small: bool) -> Element {
     }
 }
 
-async fn get_results() -> Result<String> {
-    use async_zip::base::read::mem::ZipFileReader;
-    let archive = reqwest::get(format!("{}/assets/results.zip", std::env!("URL")))
-        .await?
-        .bytes()
-        .await?
-        .to_vec();
-    let zip = ZipFileReader::new(archive).await?;
-    let mut string = String::new();
-    let mut reader = zip.reader_with_entry(0).await?;
-    reader.read_to_string_checked(&mut string).await?;
-    Ok(string)
+#[component]
+pub fn ResultsDropZone(on_file: EventHandler<(String, Vec<u8>)>) -> Element {
+    let mut dragging = use_signal(|| false);
+    let mut expectations = use_expectations();
+    let toast = use_toast();
+
+    // deqp-runner lists dropped along with the results go to the expectations shared by every page
+    let read_files = move |files: Vec<FileData>| async move {
+        for file in files {
+            let name = file.name();
+            let bytes = match file.read_bytes().await {
+                Ok(bytes) => bytes,
+                Err(e) => {
+                    error!("Failed to read {name}: {e}");
+                    continue;
+                }
+            };
+            let Some(list) = ExpectationsFile::from_file_name(&name) else {
+                on_file.call((name, bytes.to_vec()));
+                continue;
+            };
+            let loaded = match String::from_utf8(bytes.to_vec()) {
+                Ok(text) => expectations
+                    .write()
+                    .load(list, &text)
+                    .map_err(|e| e.to_string()),
+                Err(_) => Err("not UTF-8".to_string()),
+            };
+            match loaded {
+                Ok(()) => toast.success(
+                    "Success".to_string(),
+                    ToastOptions::new().description(format!("Loaded the {list} list {name}")),
+                ),
+                Err(e) => {
+                    error!("Failed to read {name}: {e}");
+                    toast.error(
+                        "Error".to_string(),
+                        ToastOptions::new().description(format!("Failed to read {name}")),
+                    );
+                }
+            }
+        }
+    };
+
+    rsx! {
+        label {
+            class: "w-full border-1 border-dashed border-slate-700 data-[dragging=true]:border-[#38bdf8] data-[dragging=true]:bg-[#38bdf8]/5 rounded-lg py-3 px-4 text-xs text-slate-400 text-center cursor-pointer hover:bg-[#38bef7]/5",
+            "data-dragging": dragging(),
+            ondragover: move |event: DragEvent| {
+                event.prevent_default();
+                dragging.set(true);
+            },
+            ondragleave: move |_| dragging.set(false),
+            ondrop: move |event: DragEvent| {
+                event.prevent_default();
+                dragging.set(false);
+                read_files(event.data_transfer().files())
+            },
+            input {
+                class: "hidden",
+                r#type: "file",
+                accept: ".csv,.zip,.bin,.txt",
+                multiple: true,
+                onchange: move |event: FormEvent| read_files(event.files()),
+            }
+            "Drop a results.csv, results.zip or results.bin here, along with its fails, flakes and skips lists, or click to browse your files"
+        }
+    }
 }
@@ -1,11 +1,73 @@
+use crate::results::{LoadPhase, use_load_progress};
 use dioxus::prelude::*;
 
 /// Default placeholder for loading state
 #[component]
 pub fn LoadingPlaceholder(message: String) -> Element {
     rsx! {
-        div { class: "rounded-radius h-[50vh] w-1/2 bg-foreground/20 animate-pulse text-foreground flex justify-center items-center place-self-center mt-14 mx-auto",
-            h3 { class: "h3", {message} }
+        div { class: "rounded-radius h-[50vh] w-1/2 bg-foreground/20 text-foreground flex flex-col space-y-4 justify-center items-center place-self-center mt-14 mx-auto",
+            h3 { class: "h3 animate-pulse", {message} }
+            div { class: "w-2/3", LoadProgressBar {} }
+        }
+    }
+}
+
+fn format_bytes(bytes: u64) -> String {
+    match bytes {
+        0..1_000 => format!("{bytes} B"),
+        1_000..1_000_000 => format!("{:.1} kB", bytes as f32 / 1e3),
+        _ => format!("{:.1} MB", bytes as f32 / 1e6),
+    }
+}
+
+fn format_eta(seconds: f32) -> String {
+    let seconds = seconds.ceil() as u64;
+    if seconds < 60 {
+        format!("{seconds}s")
+    } else {
+        format!("{}m{:02}s", seconds / 60, seconds % 60)
+    }
+}
+
+/// Bytes, rows and time left of the results being loaded, nothing once they are loaded
+#[component]
+pub fn LoadProgressBar() -> Element {
+    let Some(progress) = use_load_progress()() else {
+        return rsx! {};
+    };
+
+    let label = match progress.phase {
+        LoadPhase::Download => "Downloading results",
+        LoadPhase::Parse => "Parsing results",
+    };
+    let mut details = vec![match progress.total {
+        Some(total) => format!("{} / {}", format_bytes(progress.bytes), format_bytes(total)),
+        None => format_bytes(progress.bytes),
+    }];
+    if progress.phase == LoadPhase::Parse {
+        details.push(format!("{} rows", progress.rows));
+    }
+    if let Some(eta) = progress.eta {
+        details.push(format!("~{} left", format_eta(eta)));
+    }
+    let details = details.join(" · ");
+    // Without a total the bar only shows that something is happening
+    let percent = progress
+        .total
+        .map(|total| 100.0 * progress.bytes as f32 / total.max(1) as f32);
+
+    rsx! {
+        div { class: "w-full flex flex-col space-y-1 text-xs text-slate-400",
+            div { class: "flex flex-row justify-between gap-4",
+                span { "{label}" }
+                span { class: "text-right", "{details}" }
+            }
+            div { class: "w-full h-2 rounded-full bg-slate-800 overflow-hidden",
+                div {
+                    class: if percent.is_none() { "h-full bg-[#38bdf8] animate-pulse" } else { "h-full bg-[#38bdf8]" },
+                    style: "width: {percent.unwrap_or(100.0)}%;",
+                }
+            }
         }
     }
 }
@@ -3,21 +3,34 @@
 use dioxus::prelude::*;
 use std::time::Duration;
 
+mod compare;
 mod components;
+mod details;
+mod durations;
+mod group;
 mod landing;
 mod loader;
 mod navbar;
+mod results;
 mod routes;
+mod tree;
+mod trends;
+#[cfg(target_arch = "wasm32")]
+mod worker;
 
-use crate::routes::Route;
+use crate::{
+    results::{ActiveRun, IndexedRuns, LoadProgress, Runs},
+    routes::Route,
+};
 
 use dioxus_primitives::toast::{ToastOptions, ToastProvider, use_toast};
+use vulkan_cts_analyzer::expectations::Expectations;
 
 const FAVICON: Asset = asset!("/assets/favicon.ico");
 const TAILWIND_CSS: Asset = asset!("/assets/tailwind.css");
 const COMPONENTS_CSS: Asset = asset!("/assets/dx-components-theme.css");
 const RESULT: Asset = asset!(
-    "/assets/results.zip",
+    "/assets/results.bin",
     AssetOptions::builder().with_hash_suffix(false)
 );
 
@@ -27,6 +40,12 @@ fn main() {
 
 #[component]
 fn App() -> Element {
+    use_context_provider(|| Signal::new(Runs::default()));
+    use_context_provider(|| Signal::new(ActiveRun::default()));
+    use_context_provider(|| Signal::new(None::<LoadProgress>));
+    use_context_provider(|| Signal::new(Expectations::default()));
+    use_context_provider(|| Signal::new(IndexedRuns::default()));
+
     rsx! {
         document::Link { rel: "icon", href: FAVICON }
         document::Link { rel: "stylesheet", href: TAILWIND_CSS }
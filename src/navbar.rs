@@ -1,20 +1,69 @@
 use dioxus::prelude::*;
 
-use crate::{loader::Suspense, routes::Route};
+use crate::components::select::*;
+use crate::{
+    loader::Suspense,
+    results::{use_active_run_loader, use_runs},
+    routes::Route,
+};
 
 #[component]
 pub fn Navbar() -> Element {
+    use_active_run_loader();
+    // Keeps the results loaded from `?results=` instead of going back to the default ones
+    let results = use_runs().read().results_query();
+    let home = Route::home(results.clone());
+
     rsx! {
         div { class: "mx-auto container mb-12 py-2 px-6 sm:px-0 flex flex-row justify-between",
             Link {
                 class: "flex flex-row h-16 text-4xl md:text-5xl select-none cursor-pointer",
-                to: Route::Landing {},
+                to: home.clone(),
                 VulkanVSvg {}
                 p { class: "hidden md:block mt-auto font-bold -ml-3.5 text-[#9d1b1f]",
                     "ulkan"
                 }
                 p { class: "mt-auto ml-2 font-bold text-gray-300", "CTS Report" }
             }
+            nav { class: "mt-auto flex flex-row space-x-4 text-sm text-gray-400",
+                Link {
+                    class: "hover:underline",
+                    active_class: "text-white",
+                    to: home.clone(),
+                    "Report"
+                }
+                Link {
+                    class: "hover:underline",
+                    active_class: "text-white",
+                    to: Route::TestTree {
+                        results: results.clone(),
+                    },
+                    "Tree"
+                }
+                Link {
+                    class: "hover:underline",
+                    active_class: "text-white",
+                    to: Route::Durations {
+                        results: results.clone(),
+                    },
+                    "Durations"
+                }
+                Link {
+                    class: "hover:underline",
+                    active_class: "text-white",
+                    to: Route::Compare { results },
+                    "Compare"
+                }
+                Link {
+                    class: "hover:underline",
+                    active_class: "text-white",
+                    to: Route::Trends {
+                        index: String::new(),
+                    },
+                    "Trends"
+                }
+            }
+            RunSelector {}
         }
 
         main { class: "mx-auto container mb-24",
@@ -37,6 +86,52 @@ pub fn Navbar() -> Element {
     }
 }
 
+#[component]
+fn RunSelector() -> Element {
+    let mut runs = use_runs();
+    let Some(archive) = runs.read().archive.clone() else {
+        return rsx! {};
+    };
+    let active = runs.read().active_name().unwrap_or_default().to_string();
+
+    let options = archive.runs.iter().enumerate().map(|(i, run)| {
+        rsx! {
+            SelectOption::<usize> { index: i, value: i, text_value: "{run}",
+                "{run}"
+                SelectItemIndicator {}
+            }
+        }
+    });
+
+    rsx! {
+        div { class: "mt-auto flex flex-col items-end space-y-1 text-xs text-slate-400",
+            p { "{archive.name}" }
+            if archive.runs.len() > 1 {
+                Select::<usize> {
+                    on_value_change: move |value: Option<usize>| {
+                        if let Some(run) = value {
+                            runs.write().active = run;
+                        }
+                    },
+                    SelectTrigger {
+                        class: "select-trigger w-fit !bg-transparent !shadow-none !text-gray-300 cursor-pointer",
+                        aria_label: "Select run",
+                        SelectValue {
+                            class: "!bg-transparent !shadow-none !text-gray-300",
+                            placeholder: active,
+                        }
+                    }
+                    SelectList { aria_label: "Select run",
+                        SelectGroup { {options} }
+                    }
+                }
+            } else {
+                p { class: "text-sm text-gray-300", "{active}" }
+            }
+        }
+    }
+}
+
 #[component]
 fn VulkanVSvg() -> Element {
     rsx! {
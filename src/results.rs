@@ -0,0 +1,430 @@
+#[cfg(target_arch = "wasm32")]
+use crate::worker::WorkerArchive;
+use dioxus::CapturedError;
+use dioxus::prelude::*;
+use dioxus_primitives::toast::{ToastOptions, use_toast};
+use std::collections::HashMap;
+use std::rc::Rc;
+#[cfg(not(target_arch = "wasm32"))]
+use std::time::Instant;
+#[cfg(not(target_arch = "wasm32"))]
+use vulkan_cts_analyzer::archive;
+use vulkan_cts_analyzer::expectations::{Expectations, ExpectationsFile};
+pub use vulkan_cts_analyzer::model::*;
+
+#[derive(Clone)]
+enum Source {
+    #[cfg(not(target_arch = "wasm32"))]
+    Local(Rc<archive::Archive>),
+    // On the web everything past the download happens in the results worker
+    #[cfg(target_arch = "wasm32")]
+    Worker(Rc<WorkerArchive>),
+}
+
+// Archives are compared by identity, the same file opened twice is two archives
+impl PartialEq for Source {
+    fn eq(&self, other: &Self) -> bool {
+        match (self, other) {
+            #[cfg(not(target_arch = "wasm32"))]
+            (Self::Local(a), Self::Local(b)) => Rc::ptr_eq(a, b),
+            #[cfg(target_arch = "wasm32")]
+            (Self::Worker(a), Self::Worker(b)) => Rc::ptr_eq(a, b),
+        }
+    }
+}
+
+/// A results file: a zip archive holding one or more runs, a single CSV or preprocessed runs
+#[derive(Clone, PartialEq)]
+pub struct Archive {
+    pub name: String,
+    /// Name of every run in the archive, in archive order
+    pub runs: Vec<String>,
+    source: Source,
+}
+
+impl Archive {
+    /// Lists the runs of a results file, nothing is decompressed until a run is read
+    #[cfg(target_arch = "wasm32")]
+    pub async fn open(name: String, bytes: Vec<u8>) -> Result<Self> {
+        let (archive, runs) = WorkerArchive::open(&name, &bytes).await?;
+        Ok(Self {
+            name,
+            runs,
+            source: Source::Worker(Rc::new(archive)),
+        })
+    }
+
+    #[cfg(not(target_arch = "wasm32"))]
+    pub async fn open(name: String, bytes: Vec<u8>) -> Result<Self> {
+        let archive = archive::Archive::open(&name, bytes).await?;
+        Ok(Self {
+            name,
+            runs: archive.runs.clone(),
+            source: Source::Local(Rc::new(archive)),
+        })
+    }
+
+    /// Decompresses and parses a run, off the UI thread on the web.
+    /// `on_rows` gets the rows in batches as they are parsed, in CSV order.
+    pub async fn stream(
+        &self,
+        run: usize,
+        on_progress: impl FnMut(LoadProgress),
+        on_rows: impl FnMut(TestResults),
+    ) -> Result<()> {
+        match &self.source {
+            #[cfg(target_arch = "wasm32")]
+            Source::Worker(archive) => archive.stream(run, on_progress, on_rows).await,
+            #[cfg(not(target_arch = "wasm32"))]
+            Source::Local(archive) => stream_local(archive, run, on_progress, on_rows).await,
+        }
+    }
+
+    /// Decompresses and parses a whole run
+    pub async fn results(&self, run: usize) -> Result<TestResults> {
+        let mut results = TestResults::default();
+        self.stream(run, |_| {}, |rows| results.append(rows))
+            .await?;
+        Ok(results)
+    }
+}
+
+// Parsed chunk by chunk like in the results worker, but on the UI thread
+#[cfg(not(target_arch = "wasm32"))]
+async fn stream_local(
+    archive: &archive::Archive,
+    run: usize,
+    mut on_progress: impl FnMut(LoadProgress),
+    mut on_rows: impl FnMut(TestResults),
+) -> Result<()> {
+    let mut reader = archive.reader(run).await?;
+    loop {
+        let more = reader.read().await?;
+        if let Some(rows) = reader.batch() {
+            on_rows(rows);
+        }
+        on_progress(LoadProgress {
+            phase: LoadPhase::Parse,
+            bytes: reader.position(),
+            total: Some(reader.size()),
+            rows: reader.rows(),
+            eta: None,
+        });
+        if !more {
+            return Ok(());
+        }
+    }
+}
+
+/// Results loaded in the app, shared by every route through the context
+#[derive(Clone, Default)]
+pub struct Runs {
+    /// What the archive was loaded for, the `?results=` parameter at the time
+    pub requested: Option<String>,
+    pub archive: Option<Archive>,
+    pub active: usize,
+}
+
+impl Runs {
+    pub fn new(requested: String, archive: Archive) -> Self {
+        Self {
+            requested: Some(requested),
+            archive: Some(archive),
+            active: 0,
+        }
+    }
+
+    /// The `?results=` parameter to carry in links, so they open the same archive
+    pub fn results_query(&self) -> String {
+        self.requested.clone().unwrap_or_default()
+    }
+
+    pub fn active_name(&self) -> Option<&str> {
+        self.archive
+            .as_ref()
+            .and_then(|archive| archive.runs.get(self.active))
+            .map(String::as_str)
+    }
+}
+
+pub fn use_runs() -> Signal<Runs> {
+    use_context::<Signal<Runs>>()
+}
+
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub enum LoadPhase {
+    #[default]
+    Download,
+    Parse,
+}
+
+/// How far along the results being loaded are, shown by the loading placeholders
+#[derive(Debug, Default, Clone, Copy, PartialEq)]
+pub struct LoadProgress {
+    pub phase: LoadPhase,
+    /// Bytes downloaded, or decompressed while parsing
+    pub bytes: u64,
+    /// Unknown when the server does not send the size of the archive
+    pub total: Option<u64>,
+    pub rows: usize,
+    /// Estimated seconds left in the current phase
+    pub eta: Option<f32>,
+}
+
+/// Progress of the archive or run being loaded, `None` once everything is loaded
+pub fn use_load_progress() -> Signal<Option<LoadProgress>> {
+    use_context::<Signal<Option<LoadProgress>>>()
+}
+
+/// Runs of the archive read by the test pages, each parsed and indexed once since a test is
+/// looked up in every run
+#[derive(Clone, Default)]
+pub struct IndexedRuns {
+    pub archive: Option<Archive>,
+    pub runs: HashMap<usize, Rc<(TestResults, NameIndex)>>,
+}
+
+pub fn use_indexed_runs() -> Signal<IndexedRuns> {
+    use_context::<Signal<IndexedRuns>>()
+}
+
+/// Parsed results of the active run, shared by every route through the context
+#[derive(Clone, Default, PartialEq)]
+pub struct ActiveRun {
+    pub name: String,
+    pub results: TestResults,
+}
+
+pub fn use_active_run() -> Signal<ActiveRun> {
+    use_context::<Signal<ActiveRun>>()
+}
+
+/// Expected outcomes from deqp-runner's fails, flakes and skips lists, empty until one is loaded
+pub fn use_expectations() -> Signal<Expectations> {
+    use_context::<Signal<Expectations>>()
+}
+
+/// Keeps the active run in sync with the run selected in the archive
+pub fn use_active_run_loader() {
+    let toast = use_toast();
+    let runs = use_runs();
+    let mut active_run = use_active_run();
+    let mut progress = use_load_progress();
+
+    // Rows are shown batch after batch, so the first page renders before the whole run is parsed
+    let run = use_resource(move || async move {
+        let runs = runs.read().clone();
+        let (Some(archive), Some(name)) = (&runs.archive, runs.active_name()) else {
+            return Ok(None);
+        };
+        let name = name.to_string();
+        active_run.set(ActiveRun {
+            name: name.clone(),
+            results: TestResults::default(),
+        });
+        let streamed = archive
+            .stream(
+                runs.active,
+                |p| progress.set(Some(p)),
+                |rows| active_run.write().results.append(rows),
+            )
+            .await;
+        progress.set(None);
+        streamed?;
+        Ok::<_, CapturedError>(Some(name))
+    });
+
+    use_effect(move || match &*run.read() {
+        Some(Ok(Some(name))) => {
+            toast.success(
+                "Success".to_string(),
+                ToastOptions::new().description(format!("Successfully loaded {name}")),
+            );
+        }
+        Some(Err(e)) => {
+            error!("Failed to read results: {e}");
+            toast.error(
+                "Error".to_string(),
+                ToastOptions::new().description("Failed to read CTS results"),
+            );
+        }
+        _ => {}
+    });
+}
+
+/// Loads the archive requested by the `?results=` parameter of the route in the context, the
+/// default one when it is empty
+pub fn use_archive(requested: String) -> Resource<Result<()>> {
+    let mut runs = use_runs();
+    let mut progress = use_load_progress();
+    let mut expectations = use_expectations();
+    let toast = use_toast();
+
+    let resource = use_resource(use_reactive!(|requested| async move {
+        // Keep whatever was loaded for this URL, e.g. a file dropped by the user
+        if runs.peek().requested.as_ref() == Some(&requested) {
+            return Ok::<(), CapturedError>(());
+        }
+        let (url, config) = results_url(requested.clone()).await?;
+        progress.set(Some(LoadProgress::default()));
+        let archive = fetch_archive(&url, move |p| progress.set(Some(p))).await;
+        progress.set(None);
+        runs.set(Runs::new(requested, archive?));
+
+        // The run is usable without its expectations, failing to get them is only reported
+        let mut loaded = expectations.peek().clone();
+        match fetch_expectations(&config, &mut loaded).await {
+            Ok(true) => expectations.set(loaded),
+            Ok(false) => {}
+            Err(e) => {
+                error!("Failed to fetch expectations: {e}");
+                toast.error(
+                    "Error".to_string(),
+                    ToastOptions::new().description("Failed to fetch the expectations"),
+                );
+            }
+        }
+        Ok(())
+    }));
+
+    use_effect(move || {
+        if let Some(Err(e)) = &*resource.read() {
+            error!("Failed to fetch results: {e}");
+            toast.error(
+                "Error".to_string(),
+                ToastOptions::new().description("Failed to fetch CTS results"),
+            );
+        }
+    });
+
+    resource
+}
+
+#[derive(Default, serde::Deserialize)]
+struct Config {
+    results: Option<String>,
+    // deqp-runner lists of the results, see `ExpectationsFile`
+    fails: Option<String>,
+    flakes: Option<String>,
+    skips: Option<String>,
+}
+
+// The archive to load is picked at runtime, in order of priority:
+// the `?results=` query parameter, the `results` key of `/config.json`
+// and finally the archive bundled with the app.
+// The expectation lists of `/config.json` are loaded whichever archive is picked.
+async fn results_url(requested: String) -> Result<(String, Config)> {
+    let config = match reqwest::get(resolve_url("/config.json").await?).await {
+        Ok(response) if response.status().is_success() => {
+            serde_json::from_str::<Config>(&response.text().await?).unwrap_or_else(|e| {
+                error!("Invalid config.json: {e}");
+                Config::default()
+            })
+        }
+        _ => Config::default(),
+    };
+    let url = if requested.is_empty() {
+        config
+            .results
+            .clone()
+            .unwrap_or_else(|| crate::RESULT.to_string())
+    } else {
+        requested
+    };
+    Ok((url, config))
+}
+
+// Lists named in `/config.json` replace the ones loaded so far, `false` when it names none
+async fn fetch_expectations(config: &Config, expectations: &mut Expectations) -> Result<bool> {
+    let mut fetched = false;
+    for (file, url) in [
+        (ExpectationsFile::Fails, &config.fails),
+        (ExpectationsFile::Flakes, &config.flakes),
+        (ExpectationsFile::Skips, &config.skips),
+    ] {
+        let Some(url) = url else {
+            continue;
+        };
+        let text = reqwest::get(resolve_url(url).await?)
+            .await?
+            .error_for_status()?
+            .text()
+            .await?;
+        expectations
+            .load(file, &text)
+            .map_err(|e| CapturedError::msg(format!("{url}: {e}")))?;
+        fetched = true;
+    }
+    Ok(fetched)
+}
+
+// reqwest needs absolute URLs, relative ones are resolved against the current page
+pub async fn resolve_url(url: &str) -> Result<String> {
+    if reqwest::Url::parse(url).is_ok() {
+        return Ok(url.to_string());
+    }
+    let base = document::eval("return window.location.href;")
+        .join::<String>()
+        .await?;
+    Ok(reqwest::Url::parse(&base)?.join(url)?.to_string())
+}
+
+/// Downloads and opens an archive, on the web the download happens in the results worker
+#[cfg(target_arch = "wasm32")]
+pub async fn fetch_archive(url: &str, on_progress: impl FnMut(LoadProgress)) -> Result<Archive> {
+    let url = resolve_url(url).await?;
+    let name = url.rsplit('/').next().unwrap_or(&url).to_string();
+    let (archive, runs) = WorkerArchive::fetch(&name, &url, on_progress).await?;
+    Ok(Archive {
+        name,
+        runs,
+        source: Source::Worker(Rc::new(archive)),
+    })
+}
+
+#[cfg(not(target_arch = "wasm32"))]
+pub async fn fetch_archive(
+    url: &str,
+    mut on_progress: impl FnMut(LoadProgress),
+) -> Result<Archive> {
+    let url = resolve_url(url).await?;
+    let mut response = reqwest::get(&url).await?.error_for_status()?;
+    let total = response.content_length();
+    let start = Instant::now();
+    let mut archive = Vec::with_capacity(total.unwrap_or_default() as usize);
+    while let Some(chunk) = response.chunk().await? {
+        archive.extend_from_slice(&chunk);
+        let bytes = archive.len() as u64;
+        let total = total.filter(|total| bytes <= *total);
+        on_progress(LoadProgress {
+            phase: LoadPhase::Download,
+            bytes,
+            total,
+            rows: 0,
+            // From the average rate so far
+            eta: total
+                .map(|total| start.elapsed().as_secs_f32() * (total - bytes) as f32 / bytes as f32),
+        });
+    }
+    let name = url.rsplit('/').next().unwrap_or(&url).to_string();
+    Archive::open(name, archive).await
+}
+
+/// The name and status counts of every run of an archive. On the web they are counted in the
+/// results worker, which caches them by hash and `ETag` rather than the archive.
+#[cfg(target_arch = "wasm32")]
+pub async fn fetch_run_counts(url: &str) -> Result<Vec<(String, HashMap<TestStatus, usize>)>> {
+    let url = resolve_url(url).await?;
+    let name = url.rsplit('/').next().unwrap_or(&url).to_string();
+    WorkerArchive::count_runs(&name, &url).await
+}
+
+#[cfg(not(target_arch = "wasm32"))]
+pub async fn fetch_run_counts(url: &str) -> Result<Vec<(String, HashMap<TestStatus, usize>)>> {
+    let archive = fetch_archive(url, |_| {}).await?;
+    let mut runs = Vec::with_capacity(archive.runs.len());
+    for (i, run) in archive.runs.iter().enumerate() {
+        runs.push((run.clone(), archive.results(i).await?.status_counts()));
+    }
+    Ok(runs)
+}
@@ -1,13 +1,60 @@
 use dioxus::prelude::*;
 
-use crate::{landing::Landing, navbar::Navbar};
+use crate::{
+    compare::Compare, details::Test, durations::Durations, group::Group, landing::Landing,
+    navbar::Navbar, results::use_runs, tree::TestTree, trends::Trends,
+};
 
 #[derive(Debug, Clone, Routable, PartialEq)]
 #[rustfmt::skip]
 pub enum Route {
     #[layout(Navbar)]
-    #[route("/")]
-    Landing {},
+    #[route("/?:results&:status&:search&:sort&:page&:unexpected")]
+    Landing {
+        results: String,
+        status: String,
+        search: String,
+        sort: String,
+        page: usize,
+        unexpected: bool,
+    },
+
+    #[route("/group/:..path?:results&:status&:search&:sort&:page&:unexpected")]
+    Group {
+        path: Vec<String>,
+        results: String,
+        status: String,
+        search: String,
+        sort: String,
+        page: usize,
+        unexpected: bool,
+    },
+
+    #[route("/durations?:results")]
+    Durations {
+        results: String,
+    },
+
+    #[route("/tree?:results")]
+    TestTree {
+        results: String,
+    },
+
+    #[route("/test/:name?:results")]
+    Test {
+        name: String,
+        results: String,
+    },
+
+    #[route("/compare?:results")]
+    Compare {
+        results: String,
+    },
+
+    #[route("/trends?:index")]
+    Trends {
+        index: String,
+    },
 
     #[route("/:..route")]
     PageNotFound {
@@ -15,9 +62,45 @@ pub enum Route {
     },
 }
 
+impl Route {
+    /// Report of the results loaded for `results`, without any filter
+    pub fn home(results: String) -> Self {
+        Self::Landing {
+            results,
+            status: String::new(),
+            search: String::new(),
+            sort: String::new(),
+            page: 0,
+            unexpected: false,
+        }
+    }
+
+    /// Report of a group of tests in the results loaded for `results`, without any filter
+    pub fn group(path: Vec<String>, results: String) -> Self {
+        Self::Group {
+            path,
+            results,
+            status: String::new(),
+            search: String::new(),
+            sort: String::new(),
+            page: 0,
+            unexpected: false,
+        }
+    }
+
+    /// Details of a test in the results loaded for `results`
+    pub fn test(name: &str, results: String) -> Self {
+        Self::Test {
+            name: name.to_string(),
+            results,
+        }
+    }
+}
+
 #[component]
 fn PageNotFound(route: Vec<String>) -> Element {
     let nav = use_navigator();
+    let runs = use_runs();
 
     rsx! {
         div { class: "w-full flex justify-center my-32",
@@ -31,7 +114,7 @@ fn PageNotFound(route: Vec<String>) -> Element {
                     }
                     Link {
                         class: "mx-auto cursor-pointer hover:underline",
-                        to: Route::Landing {},
+                        to: Route::home(runs.read().results_query()),
                         "Go back home"
                     }
                 }
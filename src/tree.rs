@@ -0,0 +1,221 @@
+use crate::landing::StatusBadge;
+use crate::loader::Loader;
+use crate::results::{
+    HMSDuration, TestResults, TestStatus, pass_rate, use_active_run, use_archive, use_runs,
+};
+use crate::routes::Route;
+use dioxus::prelude::*;
+use std::collections::HashMap;
+use strum::{EnumCount, IntoEnumIterator};
+
+// Expanding a group with thousands of tests would freeze the page
+const MAX_LISTED_TESTS: usize = 100_usize;
+
+#[derive(Clone, PartialEq)]
+struct Group {
+    name: String,
+    counts: [usize; TestStatus::COUNT],
+    children: Vec<usize>,
+    // Indices of the results of the tests directly in this group
+    tests: Vec<usize>,
+}
+
+impl Group {
+    fn new(name: &str) -> Self {
+        Self {
+            name: name.to_string(),
+            counts: [0; TestStatus::COUNT],
+            children: Vec::new(),
+            tests: Vec::new(),
+        }
+    }
+
+    fn total(&self) -> usize {
+        self.counts.iter().sum()
+    }
+
+    fn pass_rate(&self) -> Option<f32> {
+        pass_rate(TestStatus::iter().map(|s| (s, self.counts[s as usize])))
+    }
+}
+
+/// Groups of the dot-separated test names, stored as an arena whose first node is the root
+#[derive(Clone, PartialEq)]
+struct Tree {
+    groups: Vec<Group>,
+}
+
+impl Tree {
+    fn new(results: &TestResults) -> Self {
+        let mut groups = vec![Group::new("")];
+        let mut lookup = HashMap::<(usize, &str), usize>::new();
+
+        for (i, test) in results.iter().enumerate() {
+            let status = test.status;
+            let mut segments = test.name.split('.').collect::<Vec<_>>();
+            // The last segment is the test itself
+            segments.pop();
+
+            let mut current = 0_usize;
+            groups[current].counts[status as usize] += 1;
+            for segment in segments {
+                current = match lookup.get(&(current, segment)) {
+                    Some(child) => *child,
+                    None => {
+                        let child = groups.len();
+                        groups.push(Group::new(segment));
+                        groups[current].children.push(child);
+                        lookup.insert((current, segment), child);
+                        child
+                    }
+                };
+                groups[current].counts[status as usize] += 1;
+            }
+            groups[current].tests.push(i);
+        }
+
+        Self { groups }
+    }
+}
+
+#[component]
+pub fn TestTree(results: String) -> Element {
+    use_archive(results).load("Loading CTS results")?;
+
+    let active_run = use_active_run();
+    let tree = use_memo(move || Tree::new(&active_run.read().results));
+
+    let roots = tree.read().groups[0].children.clone();
+
+    rsx! {
+        div {
+            class: "flex flex-col space-y-1 rounded-3xl p-4 pt-8 w-full h-fit shadow-xl shadow-slate-950 text-gray-400",
+            style: "background: linear-gradient(145deg, #020617 0, #02081f 60%, #020617 100%);",
+            for root in roots {
+                TreeGroup {
+                    key: "{root}",
+                    tree,
+                    index: root,
+                    path: Vec::new(),
+                }
+            }
+        }
+    }
+}
+
+#[component]
+fn TreeGroup(tree: Memo<Tree>, index: usize, path: Vec<String>) -> Element {
+    let depth = path.len();
+    // Top level groups (e.g. dEQP-VK) are opened by default
+    let mut expanded = use_signal(|| depth == 0);
+    let runs = use_runs();
+    let group = tree.read().groups[index].clone();
+    let mut path = path;
+    path.push(group.name.clone());
+
+    let counts = TestStatus::iter()
+        .filter(|s| group.counts[*s as usize] > 0)
+        .map(|s| {
+            rsx! {
+                span {
+                    class: "text-nowrap",
+                    style: format!("color: {};", s.color()),
+                    title: "{s}",
+                    "{s.emoji()} {group.counts[s as usize]}"
+                }
+            }
+        });
+
+    let rate = group.pass_rate();
+
+    rsx! {
+        div { style: format!("padding-left: {}rem;", depth as f32 * 1.25),
+            div {
+                class: "w-full flex flex-row flex-wrap gap-x-4 gap-y-1 items-center text-sm text-left py-1 px-2 rounded-lg hover:bg-[#38bef7]/5 cursor-pointer",
+                onclick: move |_| expanded.toggle(),
+                span { class: "w-3 text-slate-500",
+                    if expanded() {
+                        "▾"
+                    } else {
+                        "▸"
+                    }
+                }
+                Link {
+                    class: "text-gray-300 font-bold hover:underline",
+                    to: Route::group(path.clone(), runs.read().results_query()),
+                    // Opening the group page should not also toggle it here
+                    onclick: move |e: MouseEvent| e.stop_propagation(),
+                    "{group.name}"
+                }
+                span { class: "text-xs text-slate-500", "{group.total()} tests" }
+                div { class: "flex flex-row flex-wrap gap-x-3 text-xs", {counts} }
+                if let Some(rate) = rate {
+                    div { class: "ml-auto flex flex-row items-center space-x-2 text-xs",
+                        div { class: "w-24 h-2 rounded-full bg-[#ff6467]/60 overflow-hidden",
+                            div {
+                                class: "h-full bg-[#22c55e]",
+                                style: format!("width: {rate}%;"),
+                            }
+                        }
+                        span { class: "w-12 text-right", "{rate:.1}%" }
+                    }
+                }
+            }
+        }
+        if expanded() {
+            for child in group.children.iter().copied() {
+                TreeGroup {
+                    key: "{child}",
+                    tree,
+                    index: child,
+                    path: path.clone(),
+                }
+            }
+            for test in group.tests.iter().take(MAX_LISTED_TESTS).copied() {
+                TreeTest { key: "{test}", index: test, depth: depth + 1 }
+            }
+            if group.tests.len() > MAX_LISTED_TESTS {
+                p {
+                    class: "text-xs text-slate-500 py-1",
+                    style: format!("padding-left: {}rem;", (depth + 1) as f32 * 1.25 + 1.5),
+                    "… and {group.tests.len() - MAX_LISTED_TESTS} more tests"
+                }
+            }
+        }
+    }
+}
+
+#[component]
+fn TreeTest(index: usize, depth: usize) -> Element {
+    let active_run = use_active_run();
+    let runs = use_runs();
+    let run = active_run.read();
+    if index >= run.results.len() {
+        return rsx! {};
+    }
+    let test = run.results.get(index);
+    let name = test
+        .name
+        .rsplit('.')
+        .next()
+        .unwrap_or(test.name)
+        .to_string();
+
+    rsx! {
+        div {
+            class: "flex flex-row items-center gap-4 text-sm py-1 px-2 rounded-lg hover:bg-[#38bef7]/5",
+            style: format!("padding-left: {}rem;", depth as f32 * 1.25 + 1.5),
+            Link {
+                class: "grow break-all hover:underline",
+                to: Route::test(test.name, runs.read().results_query()),
+                "{name}"
+            }
+            if let Some(duration) = test.duration {
+                span { class: "text-xs text-slate-500", "{HMSDuration(duration)}" }
+            }
+            div { class: "w-32",
+                StatusBadge { status: test.status }
+            }
+        }
+    }
+}
@@ -0,0 +1,266 @@
+use crate::loader::Loader;
+use crate::results::{TestStatus, fetch_run_counts, pass_rate, resolve_url};
+use dioxus::prelude::*;
+use std::collections::HashMap;
+use strum::IntoEnumIterator;
+
+const DEFAULT_INDEX: &str = "/runs.json";
+
+const CHART_WIDTH: f32 = 800.0;
+const CHART_HEIGHT: f32 = 260.0;
+const CHART_PADDING: f32 = 40.0;
+
+/// An entry of the runs index, e.g. `{ "name": "a1b2c3", "date": "2026-01-31", "results": "https://ci/a1b2c3/results.zip" }`
+#[derive(Clone, PartialEq, serde::Deserialize)]
+struct IndexEntry {
+    name: String,
+    date: String,
+    results: String,
+    /// The run of the archive to plot, all its runs are added up without it
+    #[serde(default)]
+    run: Option<String>,
+}
+
+impl IndexEntry {
+    fn counts(
+        &self,
+        runs: Vec<(String, HashMap<TestStatus, usize>)>,
+    ) -> Result<HashMap<TestStatus, usize>, String> {
+        if let Some(run) = &self.run {
+            return runs
+                .into_iter()
+                .find(|(name, _)| name == run)
+                .map(|(_, counts)| counts)
+                .ok_or_else(|| format!("{} has no run {run}", self.results));
+        }
+        let mut total = HashMap::new();
+        for (_, counts) in runs {
+            for (status, count) in counts {
+                *total.entry(status).or_default() += count;
+            }
+        }
+        Ok(total)
+    }
+}
+
+#[derive(Clone, PartialEq)]
+struct RunStats {
+    entry: IndexEntry,
+    counts: HashMap<TestStatus, usize>,
+}
+
+impl RunStats {
+    fn pass_rate(&self) -> f32 {
+        pass_rate(self.counts.iter().map(|(s, v)| (*s, *v))).unwrap_or(0.0)
+    }
+}
+
+#[derive(Clone, PartialEq)]
+struct Series {
+    name: String,
+    color: String,
+    values: Vec<f32>,
+}
+
+async fn load_history(index: String) -> Result<Vec<RunStats>> {
+    let index = if index.is_empty() {
+        DEFAULT_INDEX.to_string()
+    } else {
+        index
+    };
+    let json = reqwest::get(resolve_url(&index).await?)
+        .await?
+        .error_for_status()?
+        .text()
+        .await?;
+    let mut entries = serde_json::from_str::<Vec<IndexEntry>>(&json)?;
+    entries.sort_by(|a, b| a.date.cmp(&b.date));
+
+    // A broken archive should not hide the rest of the history
+    let mut history = Vec::with_capacity(entries.len());
+    for entry in entries {
+        let counts = match fetch_run_counts(&entry.results).await {
+            Ok(runs) => entry.counts(runs),
+            Err(e) => Err(e.to_string()),
+        };
+        match counts {
+            Ok(counts) => history.push(RunStats { counts, entry }),
+            Err(e) => error!("Failed to load run {}: {e}", entry.name),
+        }
+    }
+    Ok(history)
+}
+
+#[component]
+pub fn Trends(index: String) -> Element {
+    let history =
+        use_resource(use_reactive!(|index| load_history(index))).load("Loading run history")?;
+
+    let history = match &*history.read() {
+        Ok(history) => history.clone(),
+        Err(e) => {
+            return rsx! {
+                p { class: "text-center text-gray-400", "Failed to load the runs index: {e}" }
+            };
+        }
+    };
+
+    if history.is_empty() {
+        return rsx! {
+            p { class: "text-center text-gray-400", "The runs index is empty" }
+        };
+    }
+
+    let labels = history
+        .iter()
+        .map(|run| format!("{} ({})", run.entry.name, run.entry.date))
+        .collect::<Vec<_>>();
+
+    let counts = TestStatus::iter()
+        .map(|s| Series {
+            name: s.to_string(),
+            color: s.color().to_string(),
+            values: history.iter().map(|run| run.counts[&s] as f32).collect(),
+        })
+        .filter(|series| series.values.iter().any(|v| *v > 0.0))
+        .collect::<Vec<_>>();
+
+    let rates = vec![Series {
+        name: "Pass rate".to_string(),
+        color: TestStatus::Pass.color().to_string(),
+        values: history.iter().map(RunStats::pass_rate).collect(),
+    }];
+
+    rsx! {
+        div {
+            class: "flex flex-col space-y-4 rounded-3xl p-4 pt-8 w-full h-fit shadow-xl shadow-slate-950",
+            style: "background: linear-gradient(145deg, #020617 0, #02081f 60%, #020617 100%);",
+            h3 { class: "text-sm text-gray-300", "Pass rate (%, skipped tests excluded)" }
+            TrendChart {
+                series: rates,
+                labels: labels.clone(),
+                max: 100.0,
+            }
+            h3 { class: "text-sm text-gray-300", "Tests per status" }
+            TrendChart {
+                series: counts.clone(),
+                labels: labels.clone(),
+                max: counts.iter().flat_map(|s| s.values.iter()).fold(0.0_f32, |acc, v| acc.max(*v)),
+            }
+            div { class: "flex flex-row flex-wrap gap-4 text-xs text-slate-400",
+                for series in counts.iter() {
+                    div { class: "flex flex-row space-x-1 items-center",
+                        div {
+                            class: "rounded-full size-3",
+                            style: format!("background-color: {};", series.color),
+                        }
+                        p { "{series.name}" }
+                    }
+                }
+            }
+            div { class: "w-full bg-gray-900 overflow-auto border-1 border-slate-700 rounded-lg text-gray-400",
+                table { class: "w-full border-collapse border-spacing-0",
+                    tr {
+                        class: "border-b-1 border-slate-700",
+                        style: "background: radial-gradient(circle at top, rgba(56, 189, 248, 0.1), rgba(15, 23, 42, 1));",
+                        th { class: "text-left uppercase bold whitespace-nowrap py-2 px-3",
+                            "Run"
+                        }
+                        th { class: "text-left uppercase bold whitespace-nowrap py-2 px-3",
+                            "Date"
+                        }
+                        for series in counts.iter() {
+                            th { class: "uppercase bold whitespace-nowrap py-2 px-3",
+                                "{series.name}"
+                            }
+                        }
+                        th { class: "uppercase bold whitespace-nowrap py-2 px-3", "Pass rate" }
+                    }
+                    for (i , run) in history.iter().enumerate() {
+                        tr { class: "text-sm hover:bg-[#38bef7]/5",
+                            td { class: "py-2 px-3", "{run.entry.name}" }
+                            td { class: "py-2 px-3", "{run.entry.date}" }
+                            for series in counts.iter() {
+                                td { class: "py-2 px-3 text-center", "{series.values[i]}" }
+                            }
+                            td { class: "py-2 px-3 text-center", "{run.pass_rate():.1}%" }
+                        }
+                    }
+                }
+            }
+        }
+    }
+}
+
+#[component]
+fn TrendChart(series: Vec<Series>, labels: Vec<String>, max: f32) -> Element {
+    let max = if max > 0.0 { max } else { 1.0 };
+    let steps = labels.len().saturating_sub(1).max(1) as f32;
+    let x = move |i: usize| CHART_PADDING + i as f32 * (CHART_WIDTH - 2.0 * CHART_PADDING) / steps;
+    let y =
+        move |v: f32| CHART_HEIGHT - CHART_PADDING - v / max * (CHART_HEIGHT - 2.0 * CHART_PADDING);
+
+    let lines = series.iter().map(|s| {
+        let points = s
+            .values
+            .iter()
+            .enumerate()
+            .map(|(i, v)| format!("{},{}", x(i), y(*v)))
+            .collect::<Vec<_>>()
+            .join(" ");
+        rsx! {
+            polyline {
+                points: "{points}",
+                fill: "none",
+                stroke: "{s.color}",
+                "stroke-width": "2",
+            }
+            for (i , v) in s.values.iter().enumerate() {
+                circle {
+                    cx: "{x(i)}",
+                    cy: "{y(*v)}",
+                    r: "3",
+                    fill: "{s.color}",
+                }
+            }
+        }
+    });
+
+    // Only a handful of run labels fit under the chart
+    let label_every = labels.len().div_ceil(8).max(1);
+
+    rsx! {
+        svg {
+            class: "w-full h-auto text-slate-500",
+            view_box: "0 0 {CHART_WIDTH} {CHART_HEIGHT}",
+            for tick in [0.0_f32, 0.5, 1.0] {
+                line {
+                    x1: "{CHART_PADDING}",
+                    x2: "{CHART_WIDTH - CHART_PADDING}",
+                    y1: "{y(tick * max)}",
+                    y2: "{y(tick * max)}",
+                    stroke: "rgba(255, 255, 255, 0.1)",
+                }
+                text {
+                    x: "{CHART_PADDING - 4.0}",
+                    y: "{y(tick * max) + 4.0}",
+                    "text-anchor": "end",
+                    "font-size": "10",
+                    fill: "currentColor",
+                    "{tick * max:.0}"
+                }
+            }
+            for (i , label) in labels.iter().enumerate().filter(|(i, _)| i % label_every == 0) {
+                text {
+                    x: "{x(i)}",
+                    y: "{CHART_HEIGHT - CHART_PADDING / 2.0}",
+                    "text-anchor": "middle",
+                    "font-size": "10",
+                    fill: "currentColor",
+                    "{label}"
+                }
+            }
+            {lines}
+        }
+    }
+}
@@ -0,0 +1,337 @@
+//! Bindings to `assets/results_worker.js`, which downloads, unzips and parses results off the UI thread
+
+use crate::results::{LoadPhase, LoadProgress, TestResults, TestStatus};
+use dioxus::CapturedError;
+use dioxus::prelude::*;
+use futures::StreamExt;
+use futures::channel::mpsc;
+use js_sys::{Array, Float32Array, Object, Reflect, Uint8Array, Uint32Array};
+use std::cell::{Cell, RefCell};
+use std::collections::HashMap;
+use std::rc::Rc;
+use strum::{EnumCount, IntoEnumIterator};
+use wasm_bindgen::JsCast;
+use wasm_bindgen::prelude::*;
+use web_sys::{ErrorEvent, MessageEvent, Worker};
+
+const RESULTS_WORKER: Asset = asset!("/assets/results_worker.js");
+// wasm-bindgen output of the `vulkan-cts-worker` crate, which does the parsing in the worker,
+// written by `build-worker.sh` before the app is built
+const WORKER_BINDINGS: Asset = asset!("/assets/worker/vulkan_cts_worker.js");
+const WORKER_WASM: Asset = asset!("/assets/worker/vulkan_cts_worker_bg.wasm");
+
+// Every message of a request goes through its channel, the reply is the last one
+type Pending = Rc<RefCell<HashMap<u32, mpsc::UnboundedSender<Result<JsValue, String>>>>>;
+
+thread_local! {
+    // Spawned on the first request and shared by every archive
+    static SHARED: RefCell<Option<Rc<ResultsWorker>>> = const { RefCell::new(None) };
+}
+
+fn js_error(e: JsValue) -> CapturedError {
+    CapturedError::msg(e.as_string().unwrap_or_else(|| format!("{e:?}")))
+}
+
+fn get(object: &JsValue, key: &str) -> Result<JsValue> {
+    Reflect::get(object, &JsValue::from_str(key)).map_err(js_error)
+}
+
+fn set(object: &Object, key: &str, value: &JsValue) -> Result<()> {
+    Reflect::set(object, &JsValue::from_str(key), value)
+        .map(|_| ())
+        .map_err(js_error)
+}
+
+fn request(kind: &str) -> Result<Object> {
+    let request = Object::new();
+    set(&request, "type", &JsValue::from_str(kind))?;
+    Ok(request)
+}
+
+struct ResultsWorker {
+    worker: Worker,
+    next_id: Cell<u32>,
+    // Replies the UI is waiting for, by request id
+    pending: Pending,
+    // Kept alive as long as the worker
+    _onmessage: Closure<dyn FnMut(MessageEvent)>,
+    _onerror: Closure<dyn FnMut(ErrorEvent)>,
+}
+
+impl ResultsWorker {
+    fn new() -> Result<Self> {
+        let url = format!("{RESULTS_WORKER}?bindings={WORKER_BINDINGS}&wasm={WORKER_WASM}");
+        let worker = Worker::new(&url).map_err(js_error)?;
+        let pending = Pending::default();
+
+        let onmessage = Closure::<dyn FnMut(MessageEvent)>::new({
+            let pending = pending.clone();
+            move |event: MessageEvent| {
+                let data = event.data();
+                // Replies to `close` carry no id, nobody waits for them
+                let Some(id) = get(&data, "id").ok().and_then(|id| id.as_f64()) else {
+                    return;
+                };
+                let mut pending = pending.borrow_mut();
+                let Some(sender) = pending.get(&(id as u32)) else {
+                    return;
+                };
+                let message = match get(&data, "error").ok().and_then(|e| e.as_string()) {
+                    Some(error) => Err(error),
+                    None => Ok(data.clone()),
+                };
+                let _ = sender.unbounded_send(message);
+                if !is_update(&data) {
+                    pending.remove(&(id as u32));
+                }
+            }
+        });
+        worker.set_onmessage(Some(onmessage.as_ref().unchecked_ref()));
+
+        // e.g. the script failed to load, nothing will ever reply
+        let onerror = Closure::<dyn FnMut(ErrorEvent)>::new({
+            let pending = pending.clone();
+            move |event: ErrorEvent| {
+                for (_, sender) in pending.borrow_mut().drain() {
+                    let _ = sender
+                        .unbounded_send(Err(format!("Results worker error: {}", event.message())));
+                }
+            }
+        });
+        worker.set_onerror(Some(onerror.as_ref().unchecked_ref()));
+
+        Ok(Self {
+            worker,
+            next_id: Cell::new(0),
+            pending,
+            _onmessage: onmessage,
+            _onerror: onerror,
+        })
+    }
+
+    fn shared() -> Result<Rc<Self>> {
+        SHARED.with(|shared| {
+            if let Some(worker) = &*shared.borrow() {
+                return Ok(worker.clone());
+            }
+            let worker = Rc::new(Self::new()?);
+            *shared.borrow_mut() = Some(worker.clone());
+            Ok(worker)
+        })
+    }
+
+    /// Sends a request and waits for its reply, the buffers in `transfer` are moved to the worker.
+    /// `on_update` gets the `progress` and `rows` messages sent before the reply.
+    async fn send(
+        &self,
+        request: Object,
+        transfer: Array,
+        mut on_update: impl FnMut(JsValue) -> Result<()>,
+    ) -> Result<JsValue> {
+        let id = self.next_id.get();
+        self.next_id.set(id.wrapping_add(1));
+        set(&request, "id", &JsValue::from(id))?;
+
+        let (sender, mut receiver) = mpsc::unbounded();
+        self.pending.borrow_mut().insert(id, sender);
+        let _in_flight = InFlight { worker: self, id };
+        self.worker
+            .post_message_with_transfer(&request, &transfer)
+            .map_err(js_error)?;
+
+        while let Some(message) = receiver.next().await {
+            let message = message.map_err(CapturedError::msg)?;
+            if !is_update(&message) {
+                return Ok(message);
+            }
+            on_update(message)?;
+        }
+        Err(CapturedError::msg("The results worker stopped"))
+    }
+}
+
+fn is_update(message: &JsValue) -> bool {
+    ["progress", "rows"]
+        .iter()
+        .any(|key| get(message, key).is_ok_and(|value| !value.is_undefined()))
+}
+
+// Stops the work in the worker when the UI drops a request before its reply
+struct InFlight<'a> {
+    worker: &'a ResultsWorker,
+    id: u32,
+}
+
+impl Drop for InFlight<'_> {
+    fn drop(&mut self) {
+        if self.worker.pending.borrow_mut().remove(&self.id).is_none() {
+            return;
+        }
+        let Ok(request) = request("cancel") else {
+            return;
+        };
+        if set(&request, "request", &JsValue::from(self.id)).is_ok() {
+            let _ = self.worker.worker.post_message(&request);
+        }
+    }
+}
+
+fn decode_progress(message: &JsValue) -> Result<LoadProgress> {
+    let progress = get(message, "progress")?;
+    let number = |key: &str| get(&progress, key).map(|value| value.as_f64());
+    Ok(LoadProgress {
+        phase: match get(&progress, "phase")?.as_string().as_deref() {
+            Some("parse") => LoadPhase::Parse,
+            _ => LoadPhase::Download,
+        },
+        bytes: number("bytes")?.unwrap_or_default() as u64,
+        total: number("total")?.map(|total| total as u64),
+        rows: number("rows")?.unwrap_or_default() as usize,
+        eta: number("eta")?.map(|eta| eta as f32),
+    })
+}
+
+fn decode_rows(message: &JsValue, statuses: &[TestStatus]) -> Result<TestResults> {
+    let rows = get(message, "rows")?;
+    let names = String::from_utf8(Uint8Array::new(&get(&rows, "names")?).to_vec())?;
+    let name_ends = Uint32Array::new(&get(&rows, "nameEnds")?).to_vec();
+    let column = Uint8Array::new(&get(&rows, "statuses")?)
+        .to_vec()
+        .into_iter()
+        .map(|s| {
+            statuses
+                .get(s as usize)
+                .copied()
+                .ok_or_else(|| CapturedError::msg(format!("Unknown status index {s}")))
+        })
+        .collect::<Result<Vec<_>>>()?;
+    let durations = Float32Array::new(&get(&rows, "durations")?).to_vec();
+    let mut counts = [0; TestStatus::COUNT];
+    for (count, value) in counts
+        .iter_mut()
+        .zip(Array::from(&get(&rows, "counts")?).iter())
+    {
+        *count = value.as_f64().unwrap_or_default() as usize;
+    }
+
+    TestResults::from_columns(names, name_ends, column, durations, counts)
+        .ok_or_else(|| CapturedError::msg("Inconsistent result columns"))
+}
+
+/// An archive held by the worker, dropped there with the last clone
+pub struct WorkerArchive {
+    worker: Rc<ResultsWorker>,
+    handle: JsValue,
+}
+
+impl WorkerArchive {
+    fn from_reply(worker: Rc<ResultsWorker>, reply: JsValue) -> Result<(Self, Vec<String>)> {
+        let runs = Array::from(&get(&reply, "runs")?)
+            .iter()
+            .filter_map(|run| run.as_string())
+            .collect();
+        let handle = get(&reply, "archive")?;
+        Ok((Self { worker, handle }, runs))
+    }
+
+    /// Downloads and lists the archive at `url` in the worker
+    pub async fn fetch(
+        name: &str,
+        url: &str,
+        mut on_progress: impl FnMut(LoadProgress),
+    ) -> Result<(Self, Vec<String>)> {
+        let worker = ResultsWorker::shared()?;
+        let request = request("fetch")?;
+        set(&request, "name", &JsValue::from_str(name))?;
+        set(&request, "url", &JsValue::from_str(url))?;
+        let reply = worker
+            .send(request, Array::new(), |message| {
+                on_progress(decode_progress(&message)?);
+                Ok(())
+            })
+            .await?;
+        Self::from_reply(worker, reply)
+    }
+
+    /// Counts the tests per status of every run of the archive at `url` in the worker, which only
+    /// keeps the counts once done
+    pub async fn count_runs(
+        name: &str,
+        url: &str,
+    ) -> Result<Vec<(String, HashMap<TestStatus, usize>)>> {
+        let worker = ResultsWorker::shared()?;
+        let request = request("counts")?;
+        set(&request, "name", &JsValue::from_str(name))?;
+        set(&request, "url", &JsValue::from_str(url))?;
+        let reply = worker.send(request, Array::new(), |_| Ok(())).await?;
+        Array::from(&get(&reply, "runs")?)
+            .iter()
+            .map(|run| {
+                let counts = get(&run, "counts")?;
+                let counts = TestStatus::iter()
+                    .map(|s| {
+                        let count = get(&counts, &s.to_string())?.as_f64().unwrap_or_default();
+                        Ok((s, count as usize))
+                    })
+                    .collect::<Result<_>>()?;
+                Ok((get(&run, "name")?.as_string().unwrap_or_default(), counts))
+            })
+            .collect()
+    }
+
+    /// Lists a results file already in memory, e.g. dropped by the user
+    pub async fn open(name: &str, bytes: &[u8]) -> Result<(Self, Vec<String>)> {
+        let worker = ResultsWorker::shared()?;
+        let bytes = Uint8Array::from(bytes);
+        let request = request("open")?;
+        set(&request, "name", &JsValue::from_str(name))?;
+        set(&request, "bytes", &bytes)?;
+        let reply = worker
+            .send(request, Array::of1(&bytes.buffer()), |_| Ok(()))
+            .await?;
+        Self::from_reply(worker, reply)
+    }
+
+    /// Decompresses and parses a run in the worker, `on_rows` gets the rows in batches as they are parsed
+    pub async fn stream(
+        &self,
+        run: usize,
+        mut on_progress: impl FnMut(LoadProgress),
+        mut on_rows: impl FnMut(TestResults),
+    ) -> Result<()> {
+        let statuses = TestStatus::iter().collect::<Vec<_>>();
+        let request = request("parse")?;
+        set(&request, "archive", &self.handle)?;
+        set(&request, "run", &JsValue::from(run as u32))?;
+        set(
+            &request,
+            "statuses",
+            &statuses
+                .iter()
+                .map(|s| JsValue::from_str(&s.to_string()))
+                .collect::<Array>(),
+        )?;
+        self.worker
+            .send(request, Array::new(), |message| {
+                if get(&message, "rows")?.is_undefined() {
+                    on_progress(decode_progress(&message)?);
+                } else {
+                    on_rows(decode_rows(&message, &statuses)?);
+                }
+                Ok(())
+            })
+            .await?;
+        Ok(())
+    }
+}
+
+impl Drop for WorkerArchive {
+    fn drop(&mut self) {
+        let Ok(request) = request("close") else {
+            return;
+        };
+        if set(&request, "archive", &self.handle).is_ok() {
+            let _ = self.worker.worker.post_message(&request);
+        }
+    }
+}
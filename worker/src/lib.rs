@@ -0,0 +1,105 @@
+//! Parsing side of the results worker, compiled to WebAssembly and loaded by
+//! `assets/results_worker.js`. The script downloads and caches the archives, this module lists and
+//! parses them with the same code as the command-line tools and the desktop viewer.
+
+use js_sys::{Array, Float32Array, Function, Object, Promise, Reflect, Uint8Array, Uint32Array};
+use std::rc::Rc;
+use vulkan_cts_analyzer::archive::{Archive, ArchiveError};
+use vulkan_cts_analyzer::model::TestResults;
+use wasm_bindgen::prelude::*;
+use wasm_bindgen_futures::{JsFuture, future_to_promise};
+
+/// A results file held by the worker
+#[wasm_bindgen]
+pub struct ResultsArchive {
+    archive: Rc<Archive>,
+}
+
+#[wasm_bindgen]
+impl ResultsArchive {
+    /// Lists the runs of a results file, nothing is decompressed until a run is parsed
+    pub async fn open(name: String, bytes: Vec<u8>) -> Result<ResultsArchive, JsError> {
+        Ok(Self {
+            archive: Rc::new(Archive::open(&name, bytes).await?),
+        })
+    }
+
+    /// Name of every run in the archive, in archive order
+    #[wasm_bindgen(getter)]
+    pub fn runs(&self) -> Vec<String> {
+        self.archive.runs.clone()
+    }
+
+    /// Decompresses and parses a run chunk by chunk. After every chunk, `on_chunk(rows, bytes, total)`
+    /// gets the next batch of rows if there is one and the decompressed bytes so far, the parsing
+    /// goes on once the promise it returns resolves and stops if it rejects.
+    pub fn parse(&self, run: usize, on_chunk: Function) -> Promise {
+        let archive = self.archive.clone();
+        future_to_promise(async move {
+            let mut reader = archive.reader(run).await.map_err(js_error)?;
+            loop {
+                let more = reader.read().await.map_err(js_error)?;
+                let rows = match reader.batch() {
+                    Some(rows) => columns(rows)?,
+                    None => JsValue::UNDEFINED,
+                };
+                let next = on_chunk.call3(
+                    &JsValue::NULL,
+                    &rows,
+                    &JsValue::from(reader.position() as f64),
+                    &JsValue::from(reader.size() as f64),
+                )?;
+                JsFuture::from(Promise::resolve(&next)).await?;
+                if !more {
+                    return Ok(JsValue::UNDEFINED);
+                }
+            }
+        })
+    }
+
+    /// Decompresses and parses a run, then resolves to its number of tests per status name
+    pub fn counts(&self, run: usize) -> Promise {
+        let archive = self.archive.clone();
+        future_to_promise(async move {
+            let results = archive.results(run).await.map_err(js_error)?;
+            let counts = Object::new();
+            for (status, count) in results.status_counts() {
+                Reflect::set(
+                    &counts,
+                    &JsValue::from_str(&status.to_string()),
+                    &JsValue::from(count as u32),
+                )?;
+            }
+            Ok(counts.into())
+        })
+    }
+}
+
+fn js_error(e: ArchiveError) -> JsValue {
+    JsError::from(e).into()
+}
+
+// The arguments of `TestResults::from_columns` as typed arrays, which the worker moves to the UI
+// thread without copying. Statuses are sent as their index in `TestStatus::iter()`.
+fn columns(rows: TestResults) -> Result<JsValue, JsValue> {
+    let (names, name_ends, statuses, durations, counts) = rows.into_columns();
+    let statuses = statuses.into_iter().map(|s| s as u8).collect::<Vec<_>>();
+    let object = Object::new();
+    for (key, value) in [
+        ("names", JsValue::from(Uint8Array::from(names.as_bytes()))),
+        ("nameEnds", Uint32Array::from(name_ends.as_slice()).into()),
+        ("statuses", Uint8Array::from(statuses.as_slice()).into()),
+        ("durations", Float32Array::from(durations.as_slice()).into()),
+        (
+            "counts",
+            counts
+                .iter()
+                .map(|count| JsValue::from(*count as u32))
+                .collect::<Array>()
+                .into(),
+        ),
+    ] {
+        Reflect::set(&object, &JsValue::from_str(key), &value)?;
+    }
+    Ok(object.into())
+}